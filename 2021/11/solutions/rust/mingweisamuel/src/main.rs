@@ -1,20 +1,11 @@
-use std::cmp::min;
-use std::io::BufRead;
+use aoc_util::grid::Grid;
+use aoc_util::input::Input;
 
-fn solve<I, const SIZE: usize>(lines: I)
-where
-    I: Iterator<Item = String>,
-{
-    let mut grid = [[0_i8; SIZE]; SIZE];
+fn main() {
+    let input = Input::stdin();
+    let mut grid = Grid::parse(input.bytes(), |c| (c - b'0') as i8);
 
     let mut big_flash = None;
-
-    for (r, line) in lines.enumerate() {
-        for (c, n) in line.bytes().map(|c| c - b'0').enumerate() {
-            grid[r][c] = n.try_into().unwrap();
-        }
-    }
-
     let mut flashes_100 = 0;
 
     for step in 1.. {
@@ -22,44 +13,33 @@ where
             break;
         }
 
-        #[allow(clippy::needless_range_loop)]
-        for r in 0..SIZE {
-            for c in 0..SIZE {
-                grid[r][c] += 1;
-            }
+        for n in grid.cells_mut() {
+            *n += 1;
         }
 
         while {
             let mut changed = false;
-            for r in 0..SIZE {
-                for c in 0..SIZE {
-                    if 10 <= grid[r][c] {
-                        #[allow(clippy::needless_range_loop)]
-                        for r2 in r.saturating_sub(1)..min(SIZE, r + 2) {
-                            for c2 in c.saturating_sub(1)..min(SIZE, c + 2) {
-                                grid[r2][c2] += 1;
-                            }
-                        }
-                        grid[r][c] = i8::MIN;
-                        changed = true;
+            for pos in grid.positions() {
+                if 10 <= grid[pos] {
+                    for pos2 in grid.neighbors8(pos) {
+                        grid[pos2] += 1;
                     }
+                    grid[pos] = i8::MIN;
+                    changed = true;
                 }
             }
             changed
         } {}
 
         let mut flashes_step = 0;
-        #[allow(clippy::needless_range_loop)]
-        for r in 0..SIZE {
-            for c in 0..SIZE {
-                if grid[r][c] < 0 {
-                    flashes_step += 1;
-                    grid[r][c] = 0;
-                }
+        for n in grid.cells_mut() {
+            if *n < 0 {
+                flashes_step += 1;
+                *n = 0;
             }
         }
 
-        if SIZE * SIZE == flashes_step {
+        if grid.cells().len() == flashes_step {
             big_flash.get_or_insert(step);
         }
         if step <= 100 {
@@ -69,18 +49,3 @@ where
 
     println!("{}\n{}", flashes_100, big_flash.unwrap());
 }
-
-fn main() {
-    let stdin = std::io::stdin();
-    let mut lines = stdin
-        .lock()
-        .lines()
-        .map(|line| line.expect("Failed to read line as UTF-8."))
-        .peekable();
-
-    match lines.peek().unwrap().len() {
-        5 => solve::<_, 5>(lines),
-        10 => solve::<_, 10>(lines),
-        n => panic!("Cannot handle {}-size grid.", n),
-    }
-}
@@ -1,6 +1,5 @@
-use priority_queue::PriorityQueue;
-use std::cmp::{max, min, Reverse};
-use std::collections::HashMap;
+use aoc_util::search::astar;
+use std::cmp::{max, min};
 use std::io::BufRead;
 
 #[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
@@ -366,31 +365,18 @@ impl<const N: usize> std::fmt::Display for BurrowState<N> {
 }
 
 fn pathfind_dist<const N: usize>(start: BurrowState<N>) -> Option<u32> {
-    let mut queue = PriorityQueue::new();
-    let mut dist: HashMap<BurrowState<N>, u32> = Default::default();
-    queue.push(start, Reverse(0));
-    dist.insert(start, 0);
-
-    while let Some((burrow, Reverse(dist_est))) = queue.pop() {
-        if burrow.is_goal() {
-            return Some(dist_est);
-        }
-
-        let d = dist[&burrow];
-        burrow.visit_neighbors(|edge_cost, next_burrow| {
-            let next_dist = d + edge_cost;
-            if dist
-                .get(&next_burrow)
-                .map(|&old_dist| next_dist < old_dist)
-                .unwrap_or(true)
-            {
-                dist.insert(next_burrow, next_dist);
-                let heuristic = next_burrow.heuristic();
-                queue.push(next_burrow, Reverse(next_dist + heuristic));
-            }
-        });
-    }
-    None
+    let neighbors = |burrow: &BurrowState<N>| {
+        let mut neighbors = Vec::new();
+        burrow.visit_neighbors(|edge_cost, next_burrow| neighbors.push((next_burrow, edge_cost)));
+        neighbors
+    };
+    astar(
+        start,
+        neighbors,
+        BurrowState::heuristic,
+        BurrowState::is_goal,
+    )
+    .map(|found| found.cost)
 }
 
 fn main() {
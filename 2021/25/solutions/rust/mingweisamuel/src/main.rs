@@ -1,4 +1,5 @@
-use std::io::BufRead;
+use aoc_util::grid::Grid;
+use aoc_util::input::Input;
 
 #[derive(Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
 #[repr(u8)]
@@ -8,66 +9,36 @@ pub enum Tile {
     South,
 }
 
-fn main() {
-    let stdin = std::io::stdin();
-    let mut grid = stdin
-        .lock()
-        .lines()
-        .map(|line| line.expect("Failed to read line as UTF-8."))
-        .map(|line| {
-            line.bytes()
-                .map(|c| match c {
-                    b'.' => Tile::Empty,
-                    b'>' => Tile::East,
-                    b'v' => Tile::South,
-                    c => panic!("Unexpect byte: {}", c),
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
+/// Moves every `herd` sea cucumber one step by `delta` if the cell there is empty.
+fn step(grid: &Grid<Tile>, herd: Tile, delta: (isize, isize), moved: &mut bool) -> Grid<Tile> {
+    let mut grid_next = Grid::new(grid.width(), grid.height(), Tile::Empty);
+    for (pos, &tile) in grid.iter() {
+        let pos_next = grid.offset_wrapping(pos, delta);
+        match tile {
+            Tile::Empty => {}
+            x if x == herd && Tile::Empty == grid[pos_next] => {
+                grid_next[pos_next] = x;
+                *moved = true;
+            }
+            x => grid_next[pos] = x,
+        }
+    }
+    grid_next
+}
 
-    let height = grid.len();
-    let width = grid[0].len();
+fn main() {
+    let input = Input::stdin();
+    let mut grid = Grid::parse(input.bytes(), |c| match c {
+        b'.' => Tile::Empty,
+        b'>' => Tile::East,
+        b'v' => Tile::South,
+        c => panic!("Unexpect byte: {}", c),
+    });
 
     for i in 1.. {
         let mut moved = false;
-        let mut grid_next = vec![vec![Tile::Empty; width]; height];
-        // Move east
-        {
-            for r in 0..height {
-                for c in 0..width {
-                    let c_next = (c + 1) % width;
-                    match grid[r][c] {
-                        Tile::Empty => {}
-                        Tile::East if Tile::Empty == grid[r][c_next] => {
-                            grid_next[r][c_next] = Tile::East;
-                            moved = true;
-                        }
-                        x => grid_next[r][c] = x,
-                    }
-                }
-            }
-        }
-        grid = grid_next;
-
-        let mut grid_next = vec![vec![Tile::Empty; width]; height];
-        // Move south
-        {
-            for r in 0..height {
-                let r_next = (r + 1) % height;
-                for c in 0..width {
-                    match grid[r][c] {
-                        Tile::Empty => {}
-                        Tile::South if Tile::Empty == grid[r_next][c] => {
-                            grid_next[r_next][c] = Tile::South;
-                            moved = true;
-                        }
-                        x => grid_next[r][c] = x,
-                    }
-                }
-            }
-        }
-        grid = grid_next;
+        grid = step(&grid, Tile::East, (0, 1), &mut moved);
+        grid = step(&grid, Tile::South, (1, 0), &mut moved);
 
         if !moved {
             println!("{}", i);
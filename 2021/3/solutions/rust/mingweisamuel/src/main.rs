@@ -1,24 +1,28 @@
-use bitvec::vec::BitVec;
+use aoc_util::bitset::BitSet;
 use std::io::BufRead;
 
-fn as_usize(bv: &BitVec) -> usize {
-    bv.iter().fold(0, |n, x| (n << 1) + (*x as usize))
+/// A row of the report, its first digit in the highest bit.
+type Row = BitSet<1>;
+
+fn as_usize(row: &Row) -> usize {
+    row.words()[0] as usize
 }
-fn common_bit(rows: &[&BitVec], i: usize) -> bool {
-    let occ = rows.iter().fold(0, |occ, row| occ + (row[i] as usize));
+fn common_bit(rows: &[&Row], i: usize) -> bool {
+    let occ = rows.iter().filter(|row| row.contains(i)).count();
     rows.len() <= 2 * occ
 }
-fn filter_bit(rows: Vec<&BitVec>, i: usize, bit: bool) -> Vec<&BitVec> {
-    rows.into_iter().filter(|row| bit == row[i]).collect()
+fn filter_bit(rows: Vec<&Row>, i: usize, bit: bool) -> Vec<&Row> {
+    rows.into_iter()
+        .filter(|row| bit == row.contains(i))
+        .collect()
 }
 
-fn find_rating(mut rows: Vec<&BitVec>, common: bool) -> Option<&BitVec> {
-    let width = rows.first()?.len();
-    for i in 0..width {
+fn find_rating(mut rows: Vec<&Row>, width: usize, common: bool) -> Option<&Row> {
+    for i in (0..width).rev() {
         if rows.len() <= 1 {
             break;
         }
-        let cb = common_bit(&*rows, i);
+        let cb = common_bit(&rows, i);
         rows = filter_bit(rows, i, common ^ cb);
     }
     if 1 < rows.len() {
@@ -29,42 +33,38 @@ fn find_rating(mut rows: Vec<&BitVec>, common: bool) -> Option<&BitVec> {
 }
 
 fn main() {
-    let rows: Vec<BitVec> = std::io::stdin()
+    let mut width = 0;
+    let rows: Vec<Row> = std::io::stdin()
         .lock()
         .lines()
         .map(|line| {
-            line.expect("Failed to read line as UTF-8.")
-                .chars()
-                .map(|c| match c {
-                    '0' => false,
-                    '1' => true,
+            let line = line.expect("Failed to read line as UTF-8.");
+            width = line.len();
+            line.bytes()
+                .rev()
+                .enumerate()
+                .filter_map(|(i, c)| match c {
+                    b'0' => None,
+                    b'1' => Some(i),
                     _ => panic!(),
                 })
                 .collect()
         })
         .collect();
+    assert!(!rows.is_empty(), "Empty input.");
 
     let count = rows.len();
-    let width = rows.first().expect("Empty input.").len();
 
-    let commons: BitVec = rows
-        .iter()
-        .fold(vec![0_usize; width], |mut occ, row| {
-            for i in 0..width {
-                occ[i] += row[i] as usize;
-            }
-            occ
-        })
-        .into_iter()
-        .map(|occ| count <= 2 * occ)
+    let commons: Row = (0..width)
+        .filter(|&i| count <= 2 * rows.iter().filter(|row| row.contains(i)).count())
         .collect();
 
     let gamma = as_usize(&commons);
-    let epsilon = ((0b1 << width) - 1) & (!gamma);
+    let epsilon = as_usize(&(Row::full(width) - commons));
 
-    let generator = find_rating(rows.iter().collect(), true);
+    let generator = find_rating(rows.iter().collect(), width, true);
     let generator = as_usize(generator.expect("Failed to determine generator rating."));
-    let scrubber = find_rating(rows.iter().collect(), false);
+    let scrubber = find_rating(rows.iter().collect(), width, false);
     let scrubber = as_usize(scrubber.expect("Failed to determine scrubber rating."));
 
     println!("{}\n{}", gamma * epsilon, generator * scrubber);
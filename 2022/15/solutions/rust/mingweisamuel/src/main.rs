@@ -1,5 +1,6 @@
 use std::{collections::HashSet, io::BufRead};
 
+use aoc_util::interval_set::IntervalSet;
 use itertools::Itertools;
 use scan_fmt::scan_fmt;
 
@@ -17,7 +18,7 @@ fn row_range(&(sx, sy, radius): &(isize, isize, usize)) -> Option<(isize, isize)
 pub fn main() {
     let mut row_beacons = HashSet::new();
 
-    let mut scans: Vec<_> = std::io::stdin()
+    let scans: Vec<_> = std::io::stdin()
         .lock()
         .lines()
         .map(|line| line.expect("Failed to read line as UTF-8."))
@@ -70,21 +71,12 @@ pub fn main() {
     // }
 
     let p1 = {
-        scans.sort_unstable_by_key(row_range);
-        let sum: usize = scans
+        let covered: IntervalSet<isize> = scans
             .iter()
             .filter_map(row_range)
-            .coalesce(|a, b| {
-                if b.0 <= a.1 + 1 {
-                    // Overlap or touching!
-                    Ok((isize::min(a.0, b.0), isize::max(a.1, b.1)))
-                } else {
-                    Err((a, b))
-                }
-            })
-            .map(|a| (a.1 - a.0 + 1) as usize)
-            .sum();
-        sum - row_beacons.len()
+            .map(|(lo, hi)| lo..=hi)
+            .collect();
+        covered.len() as usize - row_beacons.len()
     };
 
     let p2 = {
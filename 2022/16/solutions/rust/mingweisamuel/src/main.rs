@@ -1,36 +1,12 @@
-use std::{
-    collections::{BTreeMap, HashMap},
-    io::BufRead,
-};
-
-#[derive(Debug, Default, Clone, Copy)]
-pub struct BitSet {
-    bits: u64,
-}
-impl BitSet {
-    pub fn set_new(mut self, idx: u32) -> Self {
-        self.set(idx);
-        self
-    }
-    pub fn set(&mut self, idx: u32) {
-        assert!(idx < u64::BITS);
-        self.bits |= 1 << idx;
-    }
-    pub fn get(self, idx: u32) -> bool {
-        1 == 1 & (self.bits >> idx)
-    }
-    pub fn intersects(self, other: Self) -> bool {
-        0 != self.bits & other.bits
-    }
-}
+use aoc_util::bitset::BitSet;
+use std::{cmp::Reverse, collections::HashMap, io::BufRead};
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct State {
-    visited: BitSet,
+    visited: BitSet<1>,
     loc: u32,
     released: u32,
     time_used: u32,
-    second: bool,
 }
 
 const SIZE: usize = 64;
@@ -146,7 +122,7 @@ pub fn main() {
     }
 
     let p1 = find_p1(size, &dists_new, start);
-    let p2 = find_p2(size, &dists_new, start, p1);
+    let p2 = find_p2(size, &dists_new, start);
 
     println!("{}\n{}", p1, p2);
 }
@@ -157,72 +133,77 @@ fn find_p1(size: usize, dists_new: &[[u32; SIZE]; SIZE], start: u32) -> u32 {
         loc: start,
         ..Default::default()
     };
-    init.visited.set(start);
+    init.visited.insert(start as usize);
     let mut stack = vec![init];
     while let Some(state) = stack.pop() {
         if best < state.released {
             best = state.released;
         }
-        stack.extend(
-            (0..size)
-                .filter(|&neighbor| !state.visited.get(neighbor as u32))
-                .map(|neighbor| {
-                    let time_used = state.time_used + dists_new[state.loc as usize][neighbor] + 1;
-                    State {
-                        visited: state.visited.set_new(neighbor as u32),
-                        loc: neighbor as u32,
-                        released: state.released
-                            + (30_u32.saturating_sub(time_used)) * dists_new[neighbor][neighbor],
-                        time_used,
-                        second: state.second,
-                    }
-                })
-                .filter(|valve| valve.time_used < 30),
-        )
+        stack.extend(next_states(size, dists_new, state, 30));
     }
     best
 }
 
-fn find_p2(size: usize, dists_new: &[[u32; SIZE]; SIZE], start: u32, best_possible: u32) -> u32 {
-    let mut best = 0;
+fn next_states<'a>(
+    size: usize,
+    dists_new: &'a [[u32; SIZE]; SIZE],
+    state: State,
+    time: u32,
+) -> impl Iterator<Item = State> + 'a {
+    let closed = BitSet::<1>::full(size) - state.visited;
+    closed
+        .into_iter()
+        .map(move |neighbor| {
+            let time_used = state.time_used + dists_new[state.loc as usize][neighbor] + 1;
+            State {
+                visited: state.visited.with(neighbor),
+                loc: neighbor as u32,
+                released: state.released
+                    + (time.saturating_sub(time_used)) * dists_new[neighbor][neighbor],
+                time_used,
+            }
+        })
+        .filter(move |valve| valve.time_used < time)
+}
+
+/// You and the elephant open disjoint sets of valves, so find the best release for each set
+/// alone, then the best pair of disjoint sets.
+fn find_p2(size: usize, dists_new: &[[u32; SIZE]; SIZE], start: u32) -> u32 {
+    let mut best_by_set: HashMap<BitSet<1>, u32> = HashMap::new();
     let mut init = State {
         loc: start,
         ..Default::default()
     };
-    init.visited.set(start);
+    init.visited.insert(start as usize);
     let mut stack = vec![init];
     while let Some(state) = stack.pop() {
-        if best < state.released {
-            println!("{} {}: {}", state.second, state.time_used, state.released);
-            best = state.released;
+        let best = best_by_set
+            .entry(state.visited.without(start as usize))
+            .or_default();
+        if *best < state.released {
+            *best = state.released;
         }
-        if !state.second {
-            if best < best_possible + state.released {
-                stack.push(State {
-                    visited: state.visited,
-                    loc: start,
-                    released: state.released,
-                    time_used: 0,
-                    second: true,
-                });
-            }
+        stack.extend(next_states(size, dists_new, state, 26));
+    }
+
+    let mut best_by_set: Vec<_> = best_by_set.into_iter().collect();
+    best_by_set.sort_unstable_by_key(|&(_, released)| Reverse(released));
+    let lookup: HashMap<_, _> = best_by_set.iter().copied().collect();
+    let valves = BitSet::<1>::full(size).without(start as usize);
+    let max = best_by_set[0].1;
+
+    let mut best = 0;
+    for &(yours, released) in best_by_set.iter() {
+        if released + max <= best {
+            break;
+        }
+        let elephants = (valves - yours)
+            .subsets()
+            .filter_map(|elephants| lookup.get(&elephants))
+            .max();
+        if let Some(elephants) = elephants {
+            best = best.max(released + elephants);
         }
-        stack.extend(
-            (0..size)
-                .filter(|&neighbor| !state.visited.get(neighbor as u32))
-                .map(|neighbor| {
-                    let time_used = state.time_used + dists_new[state.loc as usize][neighbor] + 1;
-                    State {
-                        visited: state.visited.set_new(neighbor as u32),
-                        loc: neighbor as u32,
-                        released: state.released
-                            + (26_u32.saturating_sub(time_used)) * dists_new[neighbor][neighbor],
-                        time_used,
-                        second: state.second,
-                    }
-                })
-                .filter(|valve| valve.time_used < 26),
-        );
     }
     best
 }
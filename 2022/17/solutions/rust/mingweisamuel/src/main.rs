@@ -1,207 +1,96 @@
-use bitvec::array::BitArray;
-use bitvec::order::{BitOrder, Lsb0};
-use bitvec::slice::BitSlice;
-use bitvec::store::BitStore;
-use bitvec::vec::BitVec;
-use bitvec::BitArr;
+use aoc_util::cycle::extrapolate;
 
-use std::fmt::Write;
 use std::io::BufRead;
-use std::marker::PhantomData;
 
-pub struct Shape {
-    width: usize,
-    arrangement: BitArray<[u8; 4]>,
-}
-
-const SHAPES: &[Shape] = &[
-    Shape {
-        width: 4,
-        arrangement: BitArray {
-            _ord: PhantomData::<Lsb0>,
-            data: [0b00001111, 0b00000000, 0b00000000, 0b00000000],
-        },
-    },
-    Shape {
-        width: 3,
-        arrangement: BitArray {
-            _ord: PhantomData::<Lsb0>,
-            data: [0b00000010, 0b00000111, 0b00000010, 0b00000000],
-        },
-    },
-    Shape {
-        width: 3,
-        arrangement: BitArray {
-            _ord: PhantomData::<Lsb0>,
-            data: [0b00000111, 0b00000100, 0b00000100, 0b00000000],
-        },
-    },
-    Shape {
-        width: 1,
-        arrangement: BitArray {
-            _ord: PhantomData::<Lsb0>,
-            data: [0b00000001, 0b00000001, 0b00000001, 0b00000001],
-        },
-    },
-    Shape {
-        width: 2,
-        arrangement: BitArray {
-            _ord: PhantomData::<Lsb0>,
-            data: [0b00000011, 0b00000011, 0b00000000, 0b00000000],
-        },
-    },
+/// Each rock's rows from the bottom up, bit 6 being the leftmost column, spawned two columns in
+/// from the left wall.
+const ROCKS: &[&[u8]] = &[
+    &[0b0011110],
+    &[0b0001000, 0b0011100, 0b0001000],
+    &[0b0011100, 0b0000100, 0b0000100],
+    &[0b0010000, 0b0010000, 0b0010000, 0b0010000],
+    &[0b0011000, 0b0011000],
 ];
 
-pub fn grid_string(grid: &BitSlice<impl BitStore, impl BitOrder>) -> String {
-    assert_eq!(0, grid.len() % 8, "Grid length must be a multiple of 8.");
-    let mut out = String::new();
-    for i in (0..grid.len()).step_by(8).rev() {
-        for c in grid[i..(i + 7)].iter().by_vals() {
-            write!(out, "{}", if c { '#' } else { '.' }).unwrap();
-        }
-        writeln!(out).unwrap();
-    }
-    out
-}
-
-pub fn intersects(
-    a: &BitSlice<impl BitStore, impl BitOrder>,
-    b: &BitSlice<impl BitStore, impl BitOrder>,
-) -> bool {
-    let a = a.iter().by_vals();
-    let b = b.iter().by_vals();
-    a.zip(b).any(|(a, b)| a && b)
-}
+const LEFT_WALL: u8 = 0b1000000;
+const RIGHT_WALL: u8 = 0b0000001;
 
-pub fn can_move(
-    grid: &BitSlice<impl BitStore, impl BitOrder>,
-    shape: &Shape,
-    offset: usize,
-) -> bool {
-    !intersects(&grid[offset..], &*shape.arrangement)
+#[derive(Clone)]
+pub struct Chamber<'a> {
+    jets: &'a [isize],
+    jet: usize,
+    rocks: usize,
+    /// Bottom up, without empty rows on top.
+    rows: Vec<u8>,
 }
 
-pub fn grid_string_hypothetical(
-    grid: &BitSlice<impl BitStore, impl BitOrder>,
-    shape: &Shape,
-    offset: usize,
-) -> String {
-    assert_eq!(0, grid.len() % 8, "Grid length must be a multiple of 8.");
-    let mut out = String::new();
-    for i in (0..grid.len()).step_by(8).rev() {
-        for j in i..(i + 7) {
-            let shape = j
-                .checked_sub(offset)
-                .and_then(|i_off| shape.arrangement.get(i_off))
-                .map(|bit_ref| *bit_ref)
-                .unwrap_or(false);
-            write!(
-                out,
-                "{}",
-                if shape {
-                    '@'
-                } else if grid[j] {
-                    '#'
-                } else {
-                    '.'
-                }
-            )
-            .unwrap();
+impl<'a> Chamber<'a> {
+    pub fn new(jets: &'a [isize]) -> Self {
+        Self {
+            jets,
+            jet: 0,
+            rocks: 0,
+            rows: Vec::new(),
         }
-        // let grid_row = grid[i..(i + 7)].iter().by_vals();
-        // let shape_row = grid
-        // for c in  {
-        //     write!(out, "{}", if c { '#' } else { '.' }).unwrap();
-        // }
-        writeln!(out).unwrap();
     }
-    out
-}
-
-pub fn simulate<'a>(
-    grid: &mut BitVec<u8>,
-    mut grid_hoff: usize,
-    shapes: impl Iterator<Item = (usize, &'a Shape)>,
-    mut jets: impl Iterator<Item = isize>,
-) -> usize {
-    const SPAWN_OFFSET: usize = 3 * 8 + 2;
-    const SPAWN_PADDING: usize = 4 * 8;
 
-    for (count, shape) in shapes {
-        if 0 == count % 1_000 {
-            println!("Round {}", count);
-        }
-
-        // Offset of first cell in empty row.
-        let height_offset = grid.last_one().map(|i| i - i % 8 + 8).unwrap_or(0);
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
 
-        grid.resize(
-            std::cmp::max(
-                grid.capacity(),
-                height_offset + shape.arrangement.len() + SPAWN_PADDING,
-            ),
-            false,
-        );
+    fn collides(&self, rock: &[u8], y: usize) -> bool {
+        rock.iter()
+            .zip(self.rows.get(y..).unwrap_or_default())
+            .any(|(a, b)| 0 != a & b)
+    }
 
-        let mut shape_offset = height_offset + SPAWN_OFFSET;
-        for jet in jets.by_ref() {
-            // println!("{}", grid_string_hypothetical(&*grid, shape, shape_offset));
-            let jetted_offset = ((shape_offset as isize) + jet) as usize;
-            if jetted_offset % 8 <= 7 - shape.width && can_move(&*grid, shape, jetted_offset) {
-                shape_offset = jetted_offset;
+    /// Drops the next rock until it comes to rest.
+    pub fn drop_rock(&mut self) {
+        let mut rock = ROCKS[self.rocks % ROCKS.len()].to_vec();
+        self.rocks += 1;
+        let mut y = self.height() + 3;
+        loop {
+            let jet = self.jets[self.jet];
+            self.jet = (self.jet + 1) % self.jets.len();
+            let wall = if jet < 0 { LEFT_WALL } else { RIGHT_WALL };
+            if rock.iter().all(|row| 0 == row & wall) {
+                let pushed: Vec<_> = rock
+                    .iter()
+                    .map(|&row| if jet < 0 { row << 1 } else { row >> 1 })
+                    .collect();
+                if !self.collides(&pushed, y) {
+                    rock = pushed;
+                }
             }
-            // println!("{}", jet);
 
-            // println!("{}", grid_string_hypothetical(&*grid, shape, shape_offset));
-            if 8 <= shape_offset && can_move(&*grid, shape, shape_offset - 8) {
-                shape_offset -= 8;
-            } else {
+            if 0 == y || self.collides(&rock, y - 1) {
                 break;
             }
-            // println!("V");
+            y -= 1;
         }
 
-        grid[shape_offset..] |= shape.arrangement;
-
-        // Clear if full row (or paired full row).
-        {
-            let shape_row_offset = shape_offset - shape_offset % 8;
-            for test_row_offset in (shape_row_offset..shape_row_offset + 4 * 8).step_by(8) {
-                if 8 <= test_row_offset {
-                    let row_a = &grid[test_row_offset - 8..test_row_offset - 1];
-                    if row_a.all() {
-                        grid_hoff += test_row_offset / 8;
-
-                        let extra = grid.last_one().unwrap();
-                        if extra < test_row_offset {
-                            grid.fill(false);
-                        }
-                        else {
-                            let rows = grid[test_row_offset..grid.last_one().unwrap()].to_owned();
-                            grid.fill(false);
-                            *grid |= rows;
-                        }
-                        break;
-                    }
-                    // Strict greater than, no point of reseting if offset is already 16
-                    else if 16 < test_row_offset {
-                        let row_b = &grid[test_row_offset - 16..test_row_offset - 9];
-                        let mut row_or = BitArray::<_, Lsb0>::new([0_u8]);
-                        row_or |= row_a;
-                        row_or |= row_b;
-                        if row_or[0..7].all() {
-                            grid_hoff += test_row_offset / 8 - 2;
-
-                            let rows = grid[test_row_offset - 16..grid.last_one().unwrap()].to_owned();
-                            grid.fill(false);
-                            *grid |= rows;
-                        }
-                    }
-                }
-            }
+        if self.rows.len() < y + rock.len() {
+            self.rows.resize(y + rock.len(), 0);
         }
+        for (row, bits) in self.rows[y..].iter_mut().zip(rock) {
+            *row |= bits;
+        }
+    }
+
+    /// The next rock and jet, and how far below the top each column is filled. The usual
+    /// heuristic: it misses overhangs, so two chambers with the same key could still differ,
+    /// but the inputs don't seem to hit that.
+    pub fn key(&self) -> (usize, usize, [usize; 7]) {
+        let skyline = [0, 1, 2, 3, 4, 5, 6].map(|column| {
+            let bit = LEFT_WALL >> column;
+            self.rows
+                .iter()
+                .rev()
+                .position(|row| 0 != row & bit)
+                .unwrap_or(self.rows.len())
+        });
+        (self.rocks % ROCKS.len(), self.jet, skyline)
     }
-    grid_hoff
 }
 
 pub fn main() {
@@ -220,37 +109,16 @@ pub fn main() {
             _ => panic!(),
         })
         .collect::<Vec<_>>();
-    let mut jets = jets.into_iter().cycle();
-    let mut shapes = SHAPES.iter().cycle().enumerate();
-
-    let mut grid = BitVec::<u8>::new();
-
-    // P1
-    let grid_offset = 0;
-    let grid_hoff = simulate(
-        &mut grid,
-        grid_offset,
-        shapes.by_ref().take(2022),
-        jets.by_ref(),
-    );
-    println!("{}", grid_hoff + grid.last_one().unwrap_or(0) / 8 + 1);
-
-    // println!("{}", grid_string(&*grid));
-    // let mut prev_row = 0;
-    // for (row, i) in (0..grid.len()).step_by(8).enumerate() {
-    //     if grid[i..(i + 7)].all() {
-    //         println!("{}: {}", row - prev_row, row);
-    //         prev_row = row;
-    //     }
-    // }
-    // panic!();
 
-    // P2
-    let grid_hoff = simulate(
-        &mut grid,
-        grid_hoff,
-        shapes.by_ref().take(1_000_000_000_000 - 2022),
-        jets.by_ref(),
-    );
-    println!("{}", grid_hoff + grid.last_one().unwrap() / 8 + 1);
+    let height = |rocks| {
+        extrapolate(
+            Chamber::new(&jets),
+            rocks,
+            Chamber::drop_rock,
+            Chamber::key,
+            Chamber::height,
+        )
+    };
+    println!("{}", height(2022));
+    println!("{}", height(1_000_000_000_000));
 }
@@ -3,8 +3,8 @@ set -euo pipefail
 
 D=$(dirname $(realpath $0))
 
-echo "-- Day 16 --"
+echo "-- Day 17 --"
 printf "%-10s %-15s %-10s %-10s %-10s %-10s \n" "language" "author" "compile" "total" "average" "status"
-$D/../../lang/node.sh                   "$D/solutions/node/stelar7/main.mjs"            "$D/io/*"
+$D/../../lang/cargo_rust.sh             "$D/solutions/rust/mingweisamuel"               "$D/io/*"
 
 echo ""
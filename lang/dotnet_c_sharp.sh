@@ -14,15 +14,15 @@ AUTHOR="$SOLUTION/bin"
 while read INPUT OUTPUT; do
     CURRENT=$($D/util/start.sh)
 
-    cat $INPUT | timeout --signal=SIGKILL 20s "$EXECUTABLE" | diff --strip-trailing-cr $OUTPUT - >/dev/null
+    cat $INPUT | timeout --signal=SIGKILL 20s "$EXECUTABLE" | $D/util/check.sh "$OUTPUT" >/dev/null
     if [ $? -ne 0 ]; then
         $D/util/error.sh "C#" "$AUTHOR" "$INPUT" "$($D/util/stop.sh $CURRENT)" "$COMPILETIME"
         exit
     fi
 
     TIMES+=($($D/util/stop.sh $CURRENT))
-done < <(echo $IO_FILES | xargs -n2)
+done < <($D/util/pairs.sh $IO_FILES)
 
 TOTAL=$($D/util/stop.sh $START)
 
-$D/util/success.sh "C#" "$TOTAL" "$AUTHOR" "$TIMES" "$COMPILETIME"
+$D/util/success.sh "C#" "$TOTAL" "$AUTHOR" "$TIMES" "$COMPILETIME" "$IO_FILES"
@@ -13,15 +13,15 @@ EXECUTABLE="$(dirname $SOLUTION)/out.exe"
 while read INPUT OUTPUT; do
     CURRENT=$($D/util/start.sh)
 
-    cat $INPUT | timeout --signal=SIGKILL 20s mono "$EXECUTABLE" | diff --strip-trailing-cr $OUTPUT - >/dev/null
+    cat $INPUT | timeout --signal=SIGKILL 20s mono "$EXECUTABLE" | $D/util/check.sh "$OUTPUT" >/dev/null
     if [ $? -ne 0 ]; then
         $D/util/error.sh "C#" "$SOLUTION" "$INPUT" "$($D/util/stop.sh $CURRENT)" "$COMPILETIME"
         exit
     fi
 
     TIMES+=($($D/util/stop.sh $CURRENT))
-done < <(echo $IO_FILES | xargs -n2)
+done < <($D/util/pairs.sh $IO_FILES)
 
 TOTAL=$($D/util/stop.sh $START)
 
-$D/util/success.sh "C#" "$TOTAL" "$SOLUTION" "$TIMES" "$COMPILETIME"
\ No newline at end of file
+$D/util/success.sh "C#" "$TOTAL" "$SOLUTION" "$TIMES" "$COMPILETIME" "$IO_FILES"
\ No newline at end of file
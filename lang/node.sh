@@ -10,15 +10,15 @@ START=$($D/util/start.sh)
 while read INPUT OUTPUT; do
     CURRENT=$($D/util/start.sh)
 
-    cat $INPUT | timeout --signal=SIGKILL 20s node $SOLUTION | diff --strip-trailing-cr $OUTPUT -
+    cat $INPUT | timeout --signal=SIGKILL 20s node $SOLUTION | $D/util/check.sh "$OUTPUT"
     if [ $? -ne 0 ]; then
         $D/util/error.sh "NodeJS" "$SOLUTION" "$INPUT" "$($D/util/stop.sh $CURRENT)" "0"
         exit
     fi
 
     TIMES+=($($D/util/stop.sh $CURRENT))
-done < <(echo $IO_FILES | xargs -n2)
+done < <($D/util/pairs.sh $IO_FILES)
 
 TOTAL=$($D/util/stop.sh $START)
 
-$D/util/success.sh "NodeJS" "$TOTAL" "$SOLUTION" "$TIMES" "0"
\ No newline at end of file
+$D/util/success.sh "NodeJS" "$TOTAL" "$SOLUTION" "$TIMES" "0" "$IO_FILES"
\ No newline at end of file
@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+set -uo pipefail
+
+# Prints a leaderboard error line for every .output without a matching .input, like the
+# runner, and fails if there are any.
+STATUS=0
+for FILE in "$@"; do
+    if [[ "$FILE" == *.output && ! -f "${FILE%.output}.input" ]]; then
+        printf "%-10s %s has no matching .input ❌\n" "error" "$FILE"
+        STATUS=1
+    fi
+done
+exit $STATUS
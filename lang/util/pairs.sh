@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+set -uo pipefail
+
+# Prints "{name}.input {name}.output" for every io file pair, matched by name so a missing file
+# can't shift the other pairs. Inputs without an output are printed alone, to be run unverified
+# like the runner does. Outputs without an input are reported by orphans.sh.
+for INPUT in "$@"; do
+    case "$INPUT" in
+        *.input) ;;
+        *) continue ;;
+    esac
+
+    OUTPUT="${INPUT%.input}.output"
+    if [ -f "$OUTPUT" ]; then
+        echo "$INPUT $OUTPUT"
+    else
+        echo "$INPUT"
+    fi
+done
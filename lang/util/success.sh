@@ -7,6 +7,14 @@ TIME="$($D/timeformat.sh $2)"
 FILE="$3"
 AVERAGE="$($D/timeformat.sh $4)"
 COMPILETIME="$($D/timeformat.sh $5)"
+IO_FILES="${6:-}"
 
+# Inputs without an output were only checked for crashes, listed like the runner does.
+UNVERIFIED=""
+for INPUT in $IO_FILES; do
+    if [[ "$INPUT" == *.input && ! -f "${INPUT%.input}.output" ]]; then
+        UNVERIFIED+=" $INPUT"
+    fi
+done
 
-printf "%-10s %-15s %-10s %-10s %-10s ✅\n" "$CMD" "$(basename $(dirname -- $FILE))" "$COMPILETIME" "$TIME" "$AVERAGE"
\ No newline at end of file
+printf "%-10s %-15s %-10s %-10s %-10s ✅%s\n" "$CMD" "$(basename $(dirname -- $FILE))" "$COMPILETIME" "$TIME" "$AVERAGE" "${UNVERIFIED:+ unverified:$UNVERIFIED}"
@@ -0,0 +1,51 @@
+//! The timing loop and input loading shared by the benchmarks.
+
+use aoc_util::input::Input;
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Median time per call, over samples of enough calls to take about 10ms.
+pub fn bench<R>(name: &str, mut f: impl FnMut() -> R) {
+    let mut iters = 1;
+    loop {
+        let start = Instant::now();
+        for _ in 0..iters {
+            black_box(f());
+        }
+        if start.elapsed() > Duration::from_millis(10) {
+            break;
+        }
+        iters *= 2;
+    }
+    let mut samples: Vec<Duration> = (0..11)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..iters {
+                black_box(f());
+            }
+            start.elapsed() / iters
+        })
+        .collect();
+    samples.sort_unstable();
+    println!("{:<45} {:>12?}", name, samples[samples.len() / 2]);
+}
+
+/// Every `.input` of a day, concatenated.
+pub fn inputs(day: &str) -> Input {
+    let io_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .join(day)
+        .join("io");
+    let mut paths: Vec<_> = std::fs::read_dir(&io_dir)
+        .unwrap_or_else(|err| panic!("Failed to read {}: {}", io_dir.display(), err))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "input"))
+        .collect();
+    paths.sort();
+    let mut bytes = Vec::new();
+    for path in paths {
+        bytes.extend(std::fs::read(path).unwrap());
+    }
+    Input::from(bytes)
+}
@@ -0,0 +1,85 @@
+//! Compares `aoc_util::interval_set` with the sort-and-coalesce merging of
+//! `2022/15/solutions/rust/mingweisamuel`, sweeping rows of the repository's 2022/15 inputs.
+//! Run with `cargo bench --bench intervals`.
+
+mod common;
+
+use aoc_util::interval_set::IntervalSet;
+use aoc_util::parse::ints_array;
+use common::{bench, inputs};
+use itertools::Itertools;
+
+const RANGE: isize = 4_000_000;
+const ROWS: isize = 1000;
+
+/// The inclusive range a sensor covers on row `y`, as in 2022/15.
+fn row_range(&(sx, sy, radius): &(isize, isize, usize), y: isize) -> Option<(isize, isize)> {
+    let row_radius = radius.checked_sub(sy.abs_diff(y))? as isize;
+    Some((sx - row_radius, sx + row_radius))
+}
+
+/// Every `RANGE / ROWS`th row of `0..=RANGE`.
+fn rows() -> impl Iterator<Item = isize> {
+    (0..ROWS).map(|k| k * (RANGE / ROWS))
+}
+
+fn main() {
+    let input = inputs("2022/15");
+    // Every input's sensors together, which is just more of them per row.
+    let sensors: Vec<_> = input
+        .byte_lines()
+        .map(|line| {
+            let [sx, sy, bx, by] = ints_array::<isize, 4>(line).unwrap();
+            (sx, sy, sx.abs_diff(bx) + sy.abs_diff(by))
+        })
+        .collect();
+
+    // Covered cells plus gaps within `0..=RANGE`, summed over the rows.
+    let sorted_vec = || -> isize {
+        rows()
+            .map(|y| {
+                let mut ranges: Vec<_> = sensors.iter().filter_map(|s| row_range(s, y)).collect();
+                ranges.sort_unstable();
+                let merged: Vec<_> = ranges
+                    .into_iter()
+                    .coalesce(|a, b| {
+                        if b.0 <= a.1 + 1 {
+                            Ok((a.0, a.1.max(b.1)))
+                        } else {
+                            Err((a, b))
+                        }
+                    })
+                    .collect();
+                let covered: isize = merged.iter().map(|&(lo, hi)| hi - lo + 1).sum();
+                let mut next = 0;
+                let mut gaps = 0;
+                for &(lo, hi) in &merged {
+                    if next > RANGE {
+                        break;
+                    }
+                    gaps += (next < lo) as isize;
+                    next = next.max(hi + 1);
+                }
+                covered + gaps + (next <= RANGE) as isize
+            })
+            .sum()
+    };
+    let interval_set = || -> isize {
+        rows()
+            .map(|y| {
+                let set: IntervalSet<isize> = sensors
+                    .iter()
+                    .filter_map(|s| row_range(s, y))
+                    .map(|(lo, hi)| lo..=hi)
+                    .collect();
+                set.len() + set.gaps(0..=RANGE).count() as isize
+            })
+            .sum()
+    };
+
+    let expected = sorted_vec();
+    assert_eq!(expected, interval_set());
+    println!("2022/15, {} sensors, {} rows:", sensors.len(), ROWS);
+    bench("  mingweisamuel sort + coalesce", sorted_vec);
+    bench("  aoc_util IntervalSet", interval_set);
+}
@@ -0,0 +1,168 @@
+//! Compares `aoc_util::parse` with the parsers of existing solutions on the repository's
+//! 2021/5 and 2021/7 inputs. Run with `cargo bench`.
+
+mod common;
+
+use aoc_util::parse::{ints, ints_array};
+use common::{bench, inputs};
+use itertools::Itertools;
+use unroll::unroll_for_loops;
+
+/// `2021/5/solutions/rust/molenzwiebel`.
+mod molenzwiebel_2021_5 {
+    use itertools::Itertools;
+
+    pub type Line = ((usize, usize), (usize, usize));
+
+    // parse a 3 digit number fast by assuming it is valid
+    fn parse_fast(input: &mut impl Iterator<Item = char>) -> usize {
+        let mut num = 0;
+
+        num += (input.next().unwrap() as u8 - b'0') as usize;
+        if let Some(b) = input.next() {
+            num *= 10;
+            num += (b as u8 - b'0') as usize;
+        }
+        if let Some(b) = input.next() {
+            num *= 10;
+            num += (b as u8 - b'0') as usize;
+        }
+
+        num
+    }
+
+    pub fn line_to_tuples(line: &str) -> Line {
+        let mut iter = line.chars();
+
+        let x1 = parse_fast(&mut iter.take_while_ref(|&x| x != ','));
+
+        iter.next(); // ,
+
+        let y1 = parse_fast(&mut iter.take_while_ref(|&x| x != ' '));
+
+        iter.next(); // ' '
+        iter.next(); // -
+        iter.next(); // >
+        iter.next(); // ' '
+
+        let x2 = parse_fast(&mut iter.take_while_ref(|&x| x != ','));
+
+        iter.next(); // ,
+
+        let y2 = parse_fast(&mut iter);
+
+        ((x1, y1), (x2, y2))
+    }
+}
+
+/// `2021/7/solutions/rust/molenzwiebel`, reading from a slice instead of `stdin().bytes()`.
+// parse a 4 digit number fast by assuming it is valid
+#[unroll_for_loops]
+fn parse_fast_2021_7(input: &mut impl Iterator<Item = u8>) -> Option<u16> {
+    let mut num = 0;
+
+    if let Some(b) = input.next() {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+
+        num += (b - b'0') as u16;
+    } else {
+        return None;
+    }
+
+    for _ in 0..=2 {
+        if let Some(b) = input.next() {
+            if !b.is_ascii_digit() {
+                return Some(num);
+            }
+
+            num *= 10;
+            num += (b - b'0') as u16;
+        }
+    }
+
+    input.next(); // skip comma
+
+    Some(num)
+}
+
+fn bench_2021_5() {
+    let input = inputs("2021/5");
+    let text = input.as_str();
+    let molenzwiebel = || -> usize {
+        text.lines()
+            .map(molenzwiebel_2021_5::line_to_tuples)
+            .map(|((x1, y1), (x2, y2))| x1 + y1 + x2 + y2)
+            .sum()
+    };
+    let mingweisamuel = || -> usize {
+        text.lines()
+            .map(|line| {
+                let (src, dst) = line.split_once(" -> ").unwrap();
+                let (src_x, src_y) = src.split_once(',').unwrap();
+                let (dst_x, dst_y) = dst.split_once(',').unwrap();
+                [src_x, src_y, dst_x, dst_y]
+                    .into_iter()
+                    .map(|n| n.parse::<usize>().unwrap())
+                    .sum::<usize>()
+            })
+            .sum()
+    };
+    let ints_array = || -> usize {
+        input
+            .byte_lines()
+            .map(|line| {
+                ints_array::<usize, 4>(line)
+                    .unwrap()
+                    .into_iter()
+                    .sum::<usize>()
+            })
+            .sum()
+    };
+    let ints = || -> usize { ints::<usize>(input.bytes()).sum() };
+
+    let expected = molenzwiebel();
+    assert_eq!(expected, mingweisamuel());
+    assert_eq!(expected, ints_array());
+    assert_eq!(expected, ints());
+    println!("2021/5, {} bytes:", input.bytes().len());
+    bench("  molenzwiebel parse_fast", molenzwiebel);
+    bench("  mingweisamuel split_once + str::parse", mingweisamuel);
+    bench("  aoc_util ints_array per line", ints_array);
+    bench("  aoc_util ints", ints);
+}
+
+fn bench_2021_7() {
+    let input = inputs("2021/7");
+    let bytes = input.bytes();
+    // Each input is one line, which `parse_fast` stops at.
+    let molenzwiebel = || -> u64 {
+        input
+            .byte_lines()
+            .flat_map(|line| line.iter().copied().batching(parse_fast_2021_7))
+            .map(u64::from)
+            .sum()
+    };
+    let mingweisamuel = || -> u64 {
+        input
+            .lines()
+            .flat_map(|line| line.split(','))
+            .map(|n| n.parse::<u64>().unwrap())
+            .sum()
+    };
+    let ints = || -> u64 { ints::<u16>(bytes).map(u64::from).sum() };
+
+    let expected = molenzwiebel();
+    assert_eq!(expected, mingweisamuel());
+    assert_eq!(expected, ints());
+    println!("2021/7, {} bytes:", bytes.len());
+    bench("  molenzwiebel parse_fast", molenzwiebel);
+    bench("  mingweisamuel split + str::parse", mingweisamuel);
+    bench("  aoc_util ints", ints);
+}
+
+fn main() {
+    bench_2021_5();
+    bench_2021_7();
+}
@@ -0,0 +1,392 @@
+//! A set of small integers in `N` 64-bit words, `Copy` and hashable, e.g. the opened valves of
+//! 2022/16. The non-operator methods are `const`, so sets can be built in constants.
+
+use std::fmt;
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub, SubAssign,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitSet<const N: usize> {
+    words: [u64; N],
+}
+
+impl<const N: usize> Default for BitSet<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> BitSet<N> {
+    /// How many bits fit: the elements are `0..CAPACITY`.
+    pub const CAPACITY: usize = 64 * N;
+
+    pub const fn new() -> Self {
+        Self { words: [0; N] }
+    }
+
+    /// Bit `i` of word `i / 64` is element `i`.
+    pub const fn from_words(words: [u64; N]) -> Self {
+        Self { words }
+    }
+
+    pub const fn words(&self) -> [u64; N] {
+        self.words
+    }
+
+    /// `0..len`.
+    pub const fn full(len: usize) -> Self {
+        assert!(len <= Self::CAPACITY);
+        let mut words = [0; N];
+        let mut w = 0;
+        while w < N {
+            let start = 64 * w;
+            words[w] = if len >= start + 64 {
+                u64::MAX
+            } else if len > start {
+                (1 << (len - start)) - 1
+            } else {
+                0
+            };
+            w += 1;
+        }
+        Self { words }
+    }
+
+    /// `self` with `i` added.
+    pub const fn with(mut self, i: usize) -> Self {
+        assert!(i < Self::CAPACITY);
+        self.words[i / 64] |= 1 << (i % 64);
+        self
+    }
+
+    /// `self` with `i` removed.
+    pub const fn without(mut self, i: usize) -> Self {
+        assert!(i < Self::CAPACITY);
+        self.words[i / 64] &= !(1 << (i % 64));
+        self
+    }
+
+    pub const fn contains(&self, i: usize) -> bool {
+        i < Self::CAPACITY && 1 == 1 & (self.words[i / 64] >> (i % 64))
+    }
+
+    /// Adds `i`. Returns whether it's new.
+    pub fn insert(&mut self, i: usize) -> bool {
+        let new = !self.contains(i);
+        *self = self.with(i);
+        new
+    }
+
+    /// Removes `i`. Returns whether it was there.
+    pub fn remove(&mut self, i: usize) -> bool {
+        let was = self.contains(i);
+        *self = self.without(i);
+        was
+    }
+
+    /// How many elements.
+    pub const fn len(&self) -> usize {
+        let mut len = 0;
+        let mut w = 0;
+        while w < N {
+            len += self.words[w].count_ones() as usize;
+            w += 1;
+        }
+        len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        let mut w = 0;
+        while w < N {
+            if self.words[w] != 0 {
+                return false;
+            }
+            w += 1;
+        }
+        true
+    }
+
+    pub const fn union(mut self, other: Self) -> Self {
+        let mut w = 0;
+        while w < N {
+            self.words[w] |= other.words[w];
+            w += 1;
+        }
+        self
+    }
+
+    pub const fn intersection(mut self, other: Self) -> Self {
+        let mut w = 0;
+        while w < N {
+            self.words[w] &= other.words[w];
+            w += 1;
+        }
+        self
+    }
+
+    pub const fn difference(mut self, other: Self) -> Self {
+        let mut w = 0;
+        while w < N {
+            self.words[w] &= !other.words[w];
+            w += 1;
+        }
+        self
+    }
+
+    pub const fn symmetric_difference(mut self, other: Self) -> Self {
+        let mut w = 0;
+        while w < N {
+            self.words[w] ^= other.words[w];
+            w += 1;
+        }
+        self
+    }
+
+    /// Every element not in `self`, up to `CAPACITY`.
+    pub const fn complement(mut self) -> Self {
+        let mut w = 0;
+        while w < N {
+            self.words[w] = !self.words[w];
+            w += 1;
+        }
+        self
+    }
+
+    pub const fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(*other).is_empty()
+    }
+
+    pub const fn is_subset(&self, other: &Self) -> bool {
+        self.difference(*other).is_empty()
+    }
+
+    pub const fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    /// The smallest element.
+    pub const fn first(&self) -> Option<usize> {
+        let mut w = 0;
+        while w < N {
+            if self.words[w] != 0 {
+                return Some(64 * w + self.words[w].trailing_zeros() as usize);
+            }
+            w += 1;
+        }
+        None
+    }
+
+    /// The elements in increasing order.
+    pub fn iter(&self) -> Iter<N> {
+        Iter { set: *self }
+    }
+
+    /// Every subset of `self`, from `self` itself down to the empty set, `2^len` in all.
+    pub fn subsets(&self) -> Subsets<N> {
+        Subsets {
+            of: *self,
+            next: Some(*self),
+        }
+    }
+
+    /// Every superset of `self` within `universe`, `self` first.
+    pub fn supersets(&self, universe: Self) -> impl Iterator<Item = Self> {
+        let this = *self;
+        universe
+            .difference(this)
+            .subsets()
+            .map(move |extra| this.union(extra))
+    }
+
+    /// `self` minus one as a `64 * N`-bit number, wrapping.
+    const fn decrement(mut self) -> Self {
+        let mut w = 0;
+        while w < N {
+            let (word, borrow) = self.words[w].overflowing_sub(1);
+            self.words[w] = word;
+            if !borrow {
+                break;
+            }
+            w += 1;
+        }
+        self
+    }
+}
+
+impl<const N: usize> fmt::Debug for BitSet<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const N: usize> FromIterator<usize> for BitSet<N> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<const N: usize> Extend<usize> for BitSet<N> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for i in iter {
+            self.insert(i);
+        }
+    }
+}
+
+impl<const N: usize> IntoIterator for BitSet<N> {
+    type Item = usize;
+    type IntoIter = Iter<N>;
+
+    fn into_iter(self) -> Iter<N> {
+        self.iter()
+    }
+}
+
+impl<const N: usize> IntoIterator for &BitSet<N> {
+    type Item = usize;
+    type IntoIter = Iter<N>;
+
+    fn into_iter(self) -> Iter<N> {
+        self.iter()
+    }
+}
+
+macro_rules! operator {
+    ($op:ident, $fn:ident, $assign_op:ident, $assign_fn:ident, $method:ident) => {
+        impl<const N: usize> $op for BitSet<N> {
+            type Output = Self;
+
+            fn $fn(self, other: Self) -> Self {
+                self.$method(other)
+            }
+        }
+
+        impl<const N: usize> $assign_op for BitSet<N> {
+            fn $assign_fn(&mut self, other: Self) {
+                *self = self.$method(other);
+            }
+        }
+    };
+}
+
+operator!(BitOr, bitor, BitOrAssign, bitor_assign, union);
+operator!(BitAnd, bitand, BitAndAssign, bitand_assign, intersection);
+operator!(Sub, sub, SubAssign, sub_assign, difference);
+operator!(
+    BitXor,
+    bitxor,
+    BitXorAssign,
+    bitxor_assign,
+    symmetric_difference
+);
+
+impl<const N: usize> Not for BitSet<N> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        self.complement()
+    }
+}
+
+/// The elements of a [`BitSet`], in increasing order.
+#[derive(Debug, Clone)]
+pub struct Iter<const N: usize> {
+    set: BitSet<N>,
+}
+
+impl<const N: usize> Iterator for Iter<N> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let i = self.set.first()?;
+        // Clears the lowest set bit.
+        self.set.words[i / 64] &= self.set.words[i / 64] - 1;
+        Some(i)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.set.len();
+        (len, Some(len))
+    }
+}
+
+impl<const N: usize> ExactSizeIterator for Iter<N> {}
+
+/// The subsets of a [`BitSet`], see [`BitSet::subsets`].
+#[derive(Debug, Clone)]
+pub struct Subsets<const N: usize> {
+    of: BitSet<N>,
+    next: Option<BitSet<N>>,
+}
+
+impl<const N: usize> Iterator for Subsets<N> {
+    type Item = BitSet<N>;
+
+    fn next(&mut self) -> Option<BitSet<N>> {
+        let subset = self.next?;
+        // The next smaller number whose bits are all in `of`.
+        self.next = (!subset.is_empty()).then(|| subset.decrement().intersection(self.of));
+        Some(subset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    const VOWELS: BitSet<1> = BitSet::new().with(0).with(4).with(8).with(14).with(20);
+
+    #[test]
+    fn test_set_ops() {
+        assert_eq!(5, VOWELS.len());
+        assert!(VOWELS.contains(14) && !VOWELS.contains(1) && !VOWELS.contains(64));
+        let wide: BitSet<2> = [3, 64, 100].into_iter().collect();
+        assert_eq!(vec![3, 64, 100], wide.iter().collect::<Vec<_>>());
+        assert_eq!("{3, 64, 100}", format!("{:?}", wide));
+        let low = BitSet::<2>::full(65);
+        assert_eq!([u64::MAX, 1], low.words());
+        assert_eq!(vec![3, 64], (wide & low).iter().collect::<Vec<_>>());
+        assert_eq!(vec![100], (wide - low).iter().collect::<Vec<_>>());
+        assert_eq!(128 - 65, (!low).len());
+        assert_eq!(66, (wide | low).len());
+        assert_eq!(64, (wide ^ low).len());
+        assert!((wide & low).is_subset(&wide) && wide.is_superset(&(wide & low)));
+        assert!(!wide.is_disjoint(&low) && (wide - low).is_disjoint(&low));
+
+        let mut set = BitSet::<1>::new();
+        assert!(set.insert(5));
+        assert!(!set.insert(5));
+        assert!(set.remove(5));
+        assert!(set.is_empty() && set.first().is_none());
+        let seen: HashSet<_> = [VOWELS, VOWELS.without(0), VOWELS].into_iter().collect();
+        assert_eq!(2, seen.len());
+    }
+
+    #[test]
+    fn test_subsets() {
+        let set: BitSet<2> = [1, 63, 64].into_iter().collect();
+        let subsets: Vec<_> = set.subsets().collect();
+        assert_eq!(8, subsets.len());
+        assert_eq!(set, subsets[0]);
+        assert!(subsets[7].is_empty());
+        assert!(subsets.iter().all(|subset| subset.is_subset(&set)));
+        assert_eq!(8, subsets.iter().collect::<HashSet<_>>().len());
+        assert_eq!(1, BitSet::<1>::new().subsets().count());
+
+        let supersets: Vec<_> = BitSet::<1>::new()
+            .with(1)
+            .supersets(BitSet::full(3))
+            .collect();
+        assert_eq!(
+            vec![vec![0, 1, 2], vec![1, 2], vec![0, 1], vec![1]],
+            supersets
+                .iter()
+                .map(|set| set.iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        );
+    }
+}
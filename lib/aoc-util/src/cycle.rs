@@ -0,0 +1,230 @@
+//! Cycle detection for simulations that eventually repeat, like the falling rocks of 2022/17,
+//! and extrapolation of a quantity that grows by the same amount every cycle.
+//!
+//! [`floyd`] and [`brent`] keep O(1) states but need the whole state to compare and step it
+//! again. [`History`] hashes a key for every step instead, which may summarize the state, e.g.
+//! just the top of a tower.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{Add, Mul, Sub};
+
+/// The states of steps `start..start + len` repeat forever after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    /// The first step with the same state as step `n`.
+    pub fn equivalent(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+
+    /// The value at step `n` of a quantity that changes by the same amount every cycle, from its
+    /// values at steps `0..=start + len` at least.
+    pub fn extrapolate<Q>(&self, values: &[Q], n: usize) -> Q
+    where
+        Q: Copy + Add<Output = Q> + Sub<Output = Q> + Mul<Output = Q> + TryFrom<usize>,
+    {
+        if let Some(&value) = values.get(n) {
+            return value;
+        }
+        let end = self.start + self.len;
+        let per_cycle = values[end] - values[self.start];
+        let cycles = Q::try_from((n - self.start) / self.len)
+            .unwrap_or_else(|_| panic!("Too many cycles for the value type."));
+        values[self.equivalent(n)] + per_cycle * cycles
+    }
+}
+
+/// Floyd's tortoise and hare: the cycle of `x0, f(x0), f(f(x0)), ...`, which must have one.
+pub fn floyd<S: Clone + Eq>(x0: S, mut f: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = f(&x0);
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        let next = f(&hare);
+        hare = f(&next);
+    }
+    // The hare is now a multiple of the cycle length ahead, so they meet at its start.
+    let mut start = 0;
+    tortoise = x0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+    let mut len = 1;
+    hare = f(&tortoise);
+    while tortoise != hare {
+        hare = f(&hare);
+        len += 1;
+    }
+    Cycle { start, len }
+}
+
+/// Brent's algorithm: the same as [`floyd`] with fewer calls to `f`.
+pub fn brent<S: Clone + Eq>(x0: S, mut f: impl FnMut(&S) -> S) -> Cycle {
+    // The tortoise teleports to the hare at each power of two, until the hare comes back to it.
+    let (mut power, mut len) = (1, 1);
+    let mut tortoise = x0.clone();
+    let mut hare = f(&x0);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = f(&hare);
+        len += 1;
+    }
+    // With the hare `len` ahead, they meet at the start.
+    let mut start = 0;
+    tortoise = x0.clone();
+    hare = x0;
+    for _ in 0..len {
+        hare = f(&hare);
+    }
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+    Cycle { start, len }
+}
+
+/// The key of every step so far, to find the first one that repeats.
+#[derive(Debug, Clone)]
+pub struct History<K> {
+    steps: HashMap<K, usize>,
+}
+
+impl<K> Default for History<K> {
+    fn default() -> Self {
+        Self {
+            steps: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Eq + Hash> History<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How many steps were pushed.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Records `key` for the next step. Returns the cycle if an earlier step had the same key,
+    /// in which case nothing is recorded.
+    pub fn push(&mut self, key: K) -> Option<Cycle> {
+        let step = self.steps.len();
+        match self.steps.get(&key) {
+            Some(&start) => Some(Cycle {
+                start,
+                len: step - start,
+            }),
+            None => {
+                self.steps.insert(key, step);
+                None
+            }
+        }
+    }
+}
+
+/// The `value` of `state` after `n` calls to `step`. Stops stepping as soon as a `key` repeats
+/// and extrapolates from there, so `value` must change by the same amount every cycle.
+pub fn extrapolate<S, K, Q>(
+    mut state: S,
+    n: usize,
+    mut step: impl FnMut(&mut S),
+    mut key: impl FnMut(&S) -> K,
+    mut value: impl FnMut(&S) -> Q,
+) -> Q
+where
+    K: Eq + Hash,
+    Q: Copy + Add<Output = Q> + Sub<Output = Q> + Mul<Output = Q> + TryFrom<usize>,
+{
+    let mut history = History::new();
+    let mut values = Vec::new();
+    for _ in 0..n {
+        values.push(value(&state));
+        if let Some(cycle) = history.push(key(&state)) {
+            return cycle.extrapolate(&values, n);
+        }
+        step(&mut state);
+    }
+    value(&state)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// `x^2 + 1 mod 255` from 3: 3, 10, 101, 2, 5, 26, 167, 95, 101, ...
+    fn f(&x: &u32) -> u32 {
+        (x * x + 1) % 255
+    }
+
+    #[test]
+    fn test_floyd_brent() {
+        let expected = Cycle { start: 2, len: 6 };
+        assert_eq!(expected, floyd(3, f));
+        assert_eq!(expected, brent(3, f));
+        assert_eq!(Cycle { start: 0, len: 1 }, floyd(0, |_| 0));
+        assert_eq!(Cycle { start: 0, len: 1 }, brent(0, |_| 0));
+        let (mut floyd_calls, mut brent_calls) = (0, 0);
+        floyd(3, |x| {
+            floyd_calls += 1;
+            f(x)
+        });
+        brent(3, |x| {
+            brent_calls += 1;
+            f(x)
+        });
+        assert!(brent_calls < floyd_calls);
+
+        let mut history = History::new();
+        let cycle = std::iter::successors(Some(3), |x| Some(f(x)))
+            .find_map(|x| history.push(x))
+            .unwrap();
+        assert_eq!(expected, cycle);
+        assert_eq!(8, history.len());
+        assert_eq!(5, cycle.equivalent(5));
+        assert_eq!(3, cycle.equivalent(9));
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // A lead-in of 3 steps, then a loop of 4 steps whose value rises by 10 per lap.
+        let step = |s: &mut (usize, i64)| {
+            s.1 += if s.0 >= 3 && s.0 % 4 == 0 { 7 } else { 1 };
+            s.0 += 1;
+        };
+        let key = |&(i, _): &(usize, i64)| if i < 3 { i } else { 3 + (i - 3) % 4 };
+        let direct = |n: usize| {
+            let mut state = (0, 0);
+            (0..n).for_each(|_| step(&mut state));
+            state.1
+        };
+        for n in [0, 2, 3, 7, 8, 100, 1001] {
+            assert_eq!(direct(n), extrapolate((0, 0), n, step, key, |s| s.1));
+        }
+        assert_eq!(
+            direct(3) + 2_500_000_000 * 10,
+            extrapolate((0, 0), 3 + 10_000_000_000, step, key, |s| s.1)
+        );
+    }
+}
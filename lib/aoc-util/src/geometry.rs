@@ -0,0 +1,342 @@
+//! Integer 3D geometry: points, vectors, the 24 rotations of a cube, rigid transforms and
+//! point cloud registration, as in 2021/19's scanners.
+
+use std::collections::HashMap;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A displacement.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec3(pub [i32; 3]);
+
+/// A position.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3(pub [i32; 3]);
+
+impl Vec3 {
+    pub const ZERO: Self = Self([0; 3]);
+
+    /// Manhattan length.
+    pub fn l1(self) -> i32 {
+        self.0.iter().map(|n| n.abs()).sum()
+    }
+
+    /// Squared Euclidean length, which rotations preserve.
+    pub fn norm2(self) -> i64 {
+        self.0.iter().map(|&n| (n as i64) * (n as i64)).sum()
+    }
+}
+
+impl Point3 {
+    pub const ORIGIN: Self = Self([0; 3]);
+
+    /// The vector from the origin.
+    pub fn to_vec(self) -> Vec3 {
+        Vec3(self.0)
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self([0, 1, 2].map(|i| self.0[i] + other.0[i]))
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self([0, 1, 2].map(|i| self.0[i] - other.0[i]))
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.map(|n| -n))
+    }
+}
+
+impl Mul<i32> for Vec3 {
+    type Output = Self;
+
+    fn mul(self, k: i32) -> Self {
+        Self(self.0.map(|n| n * k))
+    }
+}
+
+impl Add<Vec3> for Point3 {
+    type Output = Self;
+
+    fn add(self, v: Vec3) -> Self {
+        Self((self.to_vec() + v).0)
+    }
+}
+
+impl AddAssign<Vec3> for Point3 {
+    fn add_assign(&mut self, v: Vec3) {
+        *self = *self + v;
+    }
+}
+
+impl Sub<Vec3> for Point3 {
+    type Output = Self;
+
+    fn sub(self, v: Vec3) -> Self {
+        Self((self.to_vec() - v).0)
+    }
+}
+
+impl SubAssign<Vec3> for Point3 {
+    fn sub_assign(&mut self, v: Vec3) {
+        *self = *self - v;
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Vec3;
+
+    fn sub(self, other: Self) -> Vec3 {
+        self.to_vec() - other.to_vec()
+    }
+}
+
+/// One of the 24 rotations of a cube: each output axis is an input axis, maybe negated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rotation([(usize, bool); 3]);
+
+impl Rotation {
+    pub const IDENTITY: Self = Self([(0, false), (1, false), (2, false)]);
+
+    /// Facing each of the 6 directions, with each of the 4 turns around it.
+    pub const ALL: [Self; 24] = [
+        Self([(0, false), (1, false), (2, false)]),
+        Self([(1, true), (0, false), (2, false)]),
+        Self([(0, true), (1, true), (2, false)]),
+        Self([(1, false), (0, true), (2, false)]),
+        Self([(1, false), (2, false), (0, false)]),
+        Self([(2, true), (1, false), (0, false)]),
+        Self([(1, true), (2, true), (0, false)]),
+        Self([(2, false), (1, true), (0, false)]),
+        Self([(2, false), (0, false), (1, false)]),
+        Self([(0, true), (2, false), (1, false)]),
+        Self([(2, true), (0, true), (1, false)]),
+        Self([(0, false), (2, true), (1, false)]),
+        Self([(1, true), (0, true), (2, true)]),
+        Self([(0, false), (1, true), (2, true)]),
+        Self([(1, false), (0, false), (2, true)]),
+        Self([(0, true), (1, false), (2, true)]),
+        Self([(0, true), (2, true), (1, true)]),
+        Self([(2, false), (0, true), (1, true)]),
+        Self([(0, false), (2, false), (1, true)]),
+        Self([(2, true), (0, false), (1, true)]),
+        Self([(2, true), (1, true), (0, true)]),
+        Self([(1, false), (2, true), (0, true)]),
+        Self([(2, false), (1, false), (0, true)]),
+        Self([(1, true), (2, false), (0, true)]),
+    ];
+
+    pub fn apply(self, v: Vec3) -> Vec3 {
+        Vec3(
+            self.0
+                .map(|(axis, negate)| if negate { -v.0[axis] } else { v.0[axis] }),
+        )
+    }
+
+    /// The rotation undoing this one.
+    pub fn inverse(self) -> Self {
+        let mut out = Self::IDENTITY;
+        for (i, (axis, negate)) in self.0.into_iter().enumerate() {
+            out.0[axis] = (i, negate);
+        }
+        out
+    }
+
+    /// `other` then `self`.
+    pub fn compose(self, other: Self) -> Self {
+        Self(self.0.map(|(axis, negate)| {
+            let (inner_axis, inner_negate) = other.0[axis];
+            (inner_axis, negate != inner_negate)
+        }))
+    }
+}
+
+impl Mul for Rotation {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.compose(other)
+    }
+}
+
+impl Mul<Vec3> for Rotation {
+    type Output = Vec3;
+
+    fn mul(self, v: Vec3) -> Vec3 {
+        self.apply(v)
+    }
+}
+
+/// A rotation about the origin, then a translation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Transform {
+    pub rotation: Rotation,
+    pub translation: Vec3,
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self {
+        rotation: Rotation::IDENTITY,
+        translation: Vec3::ZERO,
+    };
+
+    pub fn apply(&self, p: Point3) -> Point3 {
+        Point3::ORIGIN + self.rotation.apply(p.to_vec()) + self.translation
+    }
+
+    pub fn inverse(&self) -> Self {
+        let rotation = self.rotation.inverse();
+        Self {
+            rotation,
+            translation: -rotation.apply(self.translation),
+        }
+    }
+
+    /// `other` then `self`.
+    pub fn compose(&self, other: &Self) -> Self {
+        Self {
+            rotation: self.rotation.compose(other.rotation),
+            translation: self.rotation.apply(other.translation) + self.translation,
+        }
+    }
+}
+
+/// Points with their pairwise distances, for registering clouds against each other.
+#[derive(Debug, Clone)]
+pub struct PointCloud {
+    pub points: Vec<Point3>,
+    /// Squared distances between every pair of points, sorted.
+    fingerprint: Vec<i64>,
+}
+
+impl PointCloud {
+    pub fn new(points: Vec<Point3>) -> Self {
+        let mut fingerprint = Vec::with_capacity(points.len() * points.len() / 2);
+        for (i, &a) in points.iter().enumerate() {
+            fingerprint.extend(points[i + 1..].iter().map(|&b| (a - b).norm2()));
+        }
+        fingerprint.sort_unstable();
+        Self {
+            points,
+            fingerprint,
+        }
+    }
+
+    /// The transform taking `other`'s points onto at least `min_overlap` of these, if any.
+    pub fn register(&self, other: &Self, min_overlap: usize) -> Option<Transform> {
+        // Shared points mean shared distances between them, whatever the transform.
+        if common_len(&self.fingerprint, &other.fingerprint)
+            < min_overlap * min_overlap.saturating_sub(1) / 2
+        {
+            return None;
+        }
+        let mut votes = HashMap::new();
+        for rotation in Rotation::ALL {
+            votes.clear();
+            for &b in &other.points {
+                let rotated = Point3::ORIGIN + rotation.apply(b.to_vec());
+                for &a in &self.points {
+                    let translation = a - rotated;
+                    let count = votes.entry(translation).or_insert(0);
+                    *count += 1;
+                    if *count >= min_overlap {
+                        return Some(Transform {
+                            rotation,
+                            translation,
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// The size of the multiset intersection of two sorted slices.
+fn common_len(a: &[i64], b: &[i64]) -> usize {
+    let (mut i, mut j, mut count) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_rotation() {
+        let x = Vec3([1, 2, 3]);
+        for rotation in Rotation::ALL {
+            assert_eq!(x, rotation.inverse().apply(rotation.apply(x)));
+            assert_eq!(x, rotation.apply(rotation.inverse().apply(x)));
+            assert_eq!(Rotation::IDENTITY, rotation * rotation.inverse());
+        }
+        let all: HashSet<_> = Rotation::ALL.into_iter().collect();
+        assert_eq!(24, all.len());
+        for a in Rotation::ALL {
+            for b in Rotation::ALL {
+                assert!(all.contains(&(a * b)));
+                assert_eq!(a * (b * x), (a * b) * x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_transform() {
+        let t = Transform {
+            rotation: Rotation::ALL[7],
+            translation: Vec3([5, -3, 10]),
+        };
+        let u = Transform {
+            rotation: Rotation::ALL[18],
+            translation: Vec3([-1, 2, 0]),
+        };
+        let p = Point3([4, 5, -6]);
+        assert_eq!(p, t.inverse().apply(t.apply(p)));
+        assert_eq!(t.apply(u.apply(p)), t.compose(&u).apply(p));
+        assert_eq!(Transform::IDENTITY, t.compose(&t.inverse()));
+        assert_eq!(13, (Point3([1, -2, 3]) - Point3([-3, 2, -2])).l1());
+    }
+
+    #[test]
+    fn test_register() {
+        let points: Vec<_> = (0..15)
+            .map(|i| Point3([i * 7 % 11, i * i % 13, i * 3]))
+            .collect();
+        let t = Transform {
+            rotation: Rotation::ALL[13],
+            translation: Vec3([100, -20, 7]),
+        };
+        let fixed = PointCloud::new(points.clone());
+        let moved = PointCloud::new(points.iter().map(|&p| t.inverse().apply(p)).collect());
+        let found = fixed.register(&moved, 12).unwrap();
+        for &p in &moved.points {
+            assert_eq!(t.apply(p), found.apply(p));
+        }
+        let far = PointCloud::new((0..15).map(|i| Point3([i * 1000, 0, 0])).collect());
+        assert_eq!(None, fixed.register(&far, 12));
+    }
+}
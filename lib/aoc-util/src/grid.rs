@@ -0,0 +1,302 @@
+//! A rectangular grid of cells, parsed from the puzzle's characters.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// `(row, column)`, from the top left.
+pub type Pos = (usize, usize);
+
+/// `(rows, columns)` to move by.
+pub type Delta = (isize, isize);
+
+/// Up, right, down, left.
+pub const ORTHOGONAL: [Delta; 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Clockwise from up, including the diagonals.
+pub const ALL_DIRECTIONS: [Delta; 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// Cells stored row by row in one `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Cells row by row. Panics unless they fill whole rows.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.len() % width == 0,
+            "{} cells don't make rows of {}.",
+            cells.len(),
+            width
+        );
+        Self {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    /// One row per line, one cell per byte, e.g. `Grid::parse(input, |b| b - b'0')` for a
+    /// height map. Panics on rows of different widths.
+    pub fn parse(input: &(impl AsRef<[u8]> + ?Sized), mut cell: impl FnMut(u8) -> T) -> Self {
+        let mut width = None;
+        let mut cells = Vec::new();
+        for line in input.as_ref().split(|&b| b == b'\n') {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if line.is_empty() {
+                continue;
+            }
+            match width {
+                None => width = Some(line.len()),
+                Some(width) => assert_eq!(width, line.len(), "Rows differ in width."),
+            }
+            cells.extend(line.iter().map(|&b| cell(b)));
+        }
+        let width = width.unwrap_or_default();
+        Self {
+            width,
+            height: cells.len().checked_div(width).unwrap_or_default(),
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (row, col): Pos) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.0 * self.width + pos.1])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.cells[pos.0 * self.width + pos.1])
+    }
+
+    /// `pos` moved by `delta`, if that's still inside the grid.
+    pub fn offset(&self, (row, col): Pos, (dr, dc): Delta) -> Option<Pos> {
+        let pos = (row.checked_add_signed(dr)?, col.checked_add_signed(dc)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// `pos` moved by `delta`, wrapping around the edges like a torus (2021/25's sea cucumbers).
+    pub fn offset_wrapping(&self, (row, col): Pos, (dr, dc): Delta) -> Pos {
+        let wrap =
+            |n: usize, d: isize, len: usize| (n as isize + d).rem_euclid(len as isize) as usize;
+        (wrap(row, dr, self.height), wrap(col, dc, self.width))
+    }
+
+    /// Every position, row by row. Doesn't borrow the grid, so cells can be changed meanwhile.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// The positions next to `pos` in `deltas` that are inside the grid. Doesn't borrow the grid.
+    pub fn neighbors<const N: usize>(
+        &self,
+        pos: Pos,
+        deltas: [Delta; N],
+    ) -> impl Iterator<Item = Pos> {
+        let (width, height) = (self.width, self.height);
+        deltas.into_iter().filter_map(move |(dr, dc)| {
+            let row = pos.0.checked_add_signed(dr)?;
+            let col = pos.1.checked_add_signed(dc)?;
+            (row < height && col < width).then_some((row, col))
+        })
+    }
+
+    /// Up, right, down and left of `pos`, inside the grid.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        self.neighbors(pos, ORTHOGONAL)
+    }
+
+    /// All eight positions around `pos`, inside the grid.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        self.neighbors(pos, ALL_DIRECTIONS)
+    }
+
+    /// The positions from `pos` (exclusive) in direction `delta` up to the edge, e.g. the line
+    /// of sight of 2022/8's trees. `(1, 1)` walks a diagonal.
+    pub fn ray(&self, pos: Pos, delta: Delta) -> impl Iterator<Item = Pos> {
+        let (width, height) = (self.width, self.height);
+        std::iter::successors(Some(pos), move |&(row, col)| {
+            let row = row.checked_add_signed(delta.0)?;
+            let col = col.checked_add_signed(delta.1)?;
+            (row < height && col < width).then_some((row, col))
+        })
+        .skip(1)
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> std::slice::ChunksExact<'_, T> {
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "Column {} out of bounds.", col);
+        self.cells[col..].iter().step_by(self.width)
+    }
+
+    /// Cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Cells row by row.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Renders one line per row, with `cell` giving each cell's character.
+    pub fn to_string_with(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            out.extend(row.iter().map(&mut cell));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid.", pos))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid.", pos))
+    }
+}
+
+/// Back to the characters, for grids of `u8` or `char`.
+impl<T: Copy + Into<char>> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_with(|&cell| cell.into()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const HEIGHTS: &str = "2199943210\r\n3987894921\r\n9856789892\r\n";
+
+    #[test]
+    fn test_parse_display() {
+        let grid = Grid::parse(HEIGHTS, |b| b);
+        assert_eq!((10, 3), (grid.width(), grid.height()));
+        assert_eq!(b'8', grid[(1, 2)]);
+        assert_eq!(None, grid.get((3, 0)));
+        assert_eq!(HEIGHTS.replace('\r', ""), grid.to_string());
+        let digits = grid.map(|&b| b - b'0');
+        assert_eq!(vec![2, 3, 9], digits.column(0).copied().collect::<Vec<_>>());
+        assert_eq!(&[9, 8, 5, 6, 7, 8, 9, 8, 9, 2], digits.row(2));
+        assert_eq!(
+            "#.\n.#\n",
+            Grid::from_vec(2, vec![true, false, false, true]).to_string_with(|&b| if b {
+                '#'
+            } else {
+                '.'
+            })
+        );
+        assert_eq!(0, Grid::parse("", |b| b).positions().count());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(4, 3, 0);
+        assert_eq!(
+            vec![(0, 1), (1, 0)],
+            grid.neighbors4((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(8, grid.neighbors8((1, 1)).count());
+        assert_eq!(
+            vec![(1, 3), (2, 2), (1, 2)],
+            grid.neighbors8((2, 3)).collect::<Vec<_>>()
+        );
+        assert_eq!(Some((0, 3)), grid.offset((1, 2), (-1, 1)));
+        assert_eq!(None, grid.offset((1, 3), (0, 1)));
+        assert_eq!((0, 0), grid.offset_wrapping((2, 3), (1, 1)));
+        assert_eq!((2, 3), grid.offset_wrapping((0, 0), (-1, -1)));
+        assert_eq!(
+            vec![(1, 1), (2, 2)],
+            grid.ray((0, 0), (1, 1)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(1, 0), (0, 0)],
+            grid.ray((2, 0), (-1, 0)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_mutate_while_iterating() {
+        let mut grid = Grid::new(3, 3, 0u8);
+        for pos in grid.positions() {
+            for next in grid.neighbors8(pos) {
+                grid[next] += 1;
+            }
+        }
+        assert_eq!(
+            "353\n585\n353\n",
+            grid.to_string_with(|&n| (b'0' + n) as char)
+        );
+    }
+}
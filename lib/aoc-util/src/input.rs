@@ -0,0 +1,161 @@
+//! Whole-input readers. Solutions read all of stdin at once and borrow lines or paragraphs from
+//! it, instead of allocating a `String` per line with `stdin().lock().lines()`.
+
+use std::io::Read;
+
+/// The puzzle input, read from stdin at once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    bytes: Vec<u8>,
+}
+
+impl Input {
+    pub fn stdin() -> Self {
+        let mut bytes = Vec::new();
+        std::io::stdin()
+            .lock()
+            .read_to_end(&mut bytes)
+            .expect("Failed to read stdin.");
+        Self { bytes }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.bytes).expect("Failed to read input as UTF-8.")
+    }
+
+    /// Lines without their `\n` or `\r\n`.
+    pub fn lines(&self) -> std::str::Lines<'_> {
+        self.as_str().lines()
+    }
+
+    /// Lines without their `\n` or `\r\n`, for inputs that are parsed byte by byte.
+    pub fn byte_lines(&self) -> ByteLines<'_> {
+        ByteLines { rest: &self.bytes }
+    }
+
+    /// Groups of lines separated by blank lines, like 2022/1's calories per elf. Each paragraph
+    /// is returned without its final newline, so `.lines()` gives its lines.
+    pub fn paragraphs(&self) -> Paragraphs<'_> {
+        Paragraphs {
+            rest: self.as_str(),
+        }
+    }
+}
+
+impl From<Vec<u8>> for Input {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+}
+
+impl From<&str> for Input {
+    fn from(text: &str) -> Self {
+        Self {
+            bytes: text.as_bytes().to_vec(),
+        }
+    }
+}
+
+/// See [`Input::byte_lines`].
+#[derive(Debug, Clone)]
+pub struct ByteLines<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for ByteLines<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let (line, rest) = match self.rest.iter().position(|&b| b == b'\n') {
+            Some(i) => (&self.rest[..i], &self.rest[i + 1..]),
+            None => (self.rest, &[][..]),
+        };
+        self.rest = rest;
+        Some(line.strip_suffix(b"\r").unwrap_or(line))
+    }
+}
+
+/// See [`Input::paragraphs`].
+#[derive(Debug, Clone)]
+pub struct Paragraphs<'a> {
+    rest: &'a str,
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim_end_matches(['\n', '\r']).is_empty()
+}
+
+impl<'a> Iterator for Paragraphs<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Skip blank lines before the paragraph.
+        let mut start = 0;
+        for line in self.rest.split_inclusive('\n') {
+            if !is_blank(line) {
+                break;
+            }
+            start += line.len();
+        }
+        let rest = &self.rest[start..];
+        if rest.is_empty() {
+            self.rest = rest;
+            return None;
+        }
+        let mut end = 0;
+        for line in rest.split_inclusive('\n') {
+            if is_blank(line) {
+                break;
+            }
+            end += line.len();
+        }
+        self.rest = &rest[end..];
+        Some(rest[..end].trim_end_matches(['\n', '\r']))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let input = Input::from("1000\r\n2000\n\n3000");
+        assert_eq!(
+            vec!["1000", "2000", "", "3000"],
+            input.lines().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![&b"1000"[..], b"2000", b"", b"3000"],
+            input.byte_lines().collect::<Vec<_>>()
+        );
+        assert_eq!(0, Input::from("").byte_lines().count());
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let input = Input::from("1000\n2000\n\n4000\n\r\n\n5000\r\n6000\r\n\n");
+        let paragraphs: Vec<_> = input.paragraphs().collect();
+        assert_eq!(vec!["1000\n2000", "4000", "5000\r\n6000"], paragraphs);
+        assert_eq!(
+            vec!["5000", "6000"],
+            paragraphs[2].lines().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["a"],
+            Input::from("\n\na").paragraphs().collect::<Vec<_>>()
+        );
+        assert_eq!(0, Input::from("\n").paragraphs().count());
+    }
+}
@@ -0,0 +1,333 @@
+//! A set of integers stored as sorted, disjoint ranges, for unions of intervals like the
+//! sensor coverage of a row in 2022/15.
+
+use std::fmt;
+use std::ops::{Add, Bound, Range, RangeBounds, RangeInclusive, Sub};
+
+/// The primitive integers.
+pub trait Int: Copy + Ord + Add<Output = Self> + Sub<Output = Self> + fmt::Debug {
+    const ZERO: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    /// `self + 1`, saturating.
+    fn succ(self) -> Self;
+}
+
+macro_rules! int {
+    ($($t:ty),*) => {
+        $(
+            impl Int for $t {
+                const ZERO: Self = 0;
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn succ(self) -> Self {
+                    self.saturating_add(1)
+                }
+            }
+        )*
+    };
+}
+int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// Any range of integers as a half-open one. Unbounded ends stop at `T::MIN` and `T::MAX`,
+/// so `T::MAX` itself can't be in a set.
+fn half_open<T: Int>(range: impl RangeBounds<T>) -> Range<T> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.succ(),
+        Bound::Unbounded => T::MIN,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.succ(),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => T::MAX,
+    };
+    start..end
+}
+
+/// A set of integers. Every method taking a range takes any kind, e.g. `3..=7`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    /// Sorted, non-empty, and neither overlapping nor touching.
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: Int> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The maximal ranges in the set, in order.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    /// How many integers, in all ranges.
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |len, range| len + (range.end - range.start))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The smallest range containing the whole set.
+    pub fn span(&self) -> Option<Range<T>> {
+        Some(self.ranges.first()?.start..self.ranges.last()?.end)
+    }
+
+    /// Adds every integer of `range`, merging it with the ranges it overlaps or touches.
+    pub fn insert(&mut self, range: impl RangeBounds<T>) {
+        let range = half_open(range);
+        if range.is_empty() {
+            return;
+        }
+        let i = self.ranges.partition_point(|r| r.end < range.start);
+        let j = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if i < j {
+            range.start.min(self.ranges[i].start)..range.end.max(self.ranges[j - 1].end)
+        } else {
+            range
+        };
+        self.ranges.splice(i..j, [merged]);
+    }
+
+    /// Removes every integer of `range`, splitting the range it falls inside if any.
+    pub fn remove(&mut self, range: impl RangeBounds<T>) {
+        let range = half_open(range);
+        if range.is_empty() {
+            return;
+        }
+        let i = self.ranges.partition_point(|r| r.end <= range.start);
+        let j = self.ranges.partition_point(|r| r.start < range.end);
+        if i == j {
+            return;
+        }
+        let left = self.ranges[i].start..range.start;
+        let right = range.end..self.ranges[j - 1].end;
+        self.ranges.splice(
+            i..j,
+            [left, right].into_iter().filter(|piece| !piece.is_empty()),
+        );
+    }
+
+    /// The index of the first range ending after `value`.
+    fn first_ending_after(&self, value: T) -> usize {
+        self.ranges.partition_point(|r| r.end <= value)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.first_ending_after(value);
+        i < self.ranges.len() && self.ranges[i].start <= value
+    }
+
+    /// Whether every integer of `range` is in the set.
+    pub fn contains_range(&self, range: impl RangeBounds<T>) -> bool {
+        let range = half_open(range);
+        if range.is_empty() {
+            return true;
+        }
+        let i = self.first_ending_after(range.start);
+        i < self.ranges.len()
+            && self.ranges[i].start <= range.start
+            && range.end <= self.ranges[i].end
+    }
+
+    /// Whether any integer of `range` is in the set.
+    pub fn overlaps(&self, range: impl RangeBounds<T>) -> bool {
+        let range = half_open(range);
+        if range.is_empty() {
+            return false;
+        }
+        let i = self.first_ending_after(range.start);
+        i < self.ranges.len() && self.ranges[i].start < range.end
+    }
+
+    /// The maximal ranges of `within` not in the set, in order.
+    pub fn gaps(&self, within: impl RangeBounds<T>) -> impl Iterator<Item = Range<T>> + '_ {
+        let within = half_open(within);
+        let mut next = within.start;
+        let mut ranges = self.ranges[self.first_ending_after(within.start)..]
+            .iter()
+            .take_while(move |r| r.start < within.end);
+        std::iter::from_fn(move || {
+            while next < within.end {
+                let gap = match ranges.next() {
+                    Some(r) => std::mem::replace(&mut next, r.end)..r.start,
+                    None => std::mem::replace(&mut next, within.end)..within.end,
+                };
+                if !gap.is_empty() {
+                    return Some(gap);
+                }
+            }
+            None
+        })
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut ranges: Vec<Range<T>> = Vec::with_capacity(self.ranges.len() + other.ranges.len());
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        // Merges the two sorted lists, coalescing as it goes.
+        while let Some(next) = match (a.peek(), b.peek()) {
+            (Some(x), Some(y)) if y.start < x.start => b.next(),
+            (Some(_), _) => a.next(),
+            (None, _) => b.next(),
+        } {
+            match ranges.last_mut() {
+                Some(last) if next.start <= last.end => last.end = last.end.max(next.end),
+                _ => ranges.push(next.clone()),
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let both = a.start.max(b.start)..a.end.min(b.end);
+            if !both.is_empty() {
+                ranges.push(both);
+            }
+            // Whichever ends first can't overlap anything further.
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for range in &other.ranges {
+            out.remove(range.clone());
+        }
+        out
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(&self.ranges).finish()
+    }
+}
+
+impl<T: Int> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Int> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Int> Extend<Range<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+impl<T: Int> Extend<RangeInclusive<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set: IntervalSet<i32> = [0..=3, 10..=12, 5..=6].into_iter().collect();
+        assert_eq!(&[0..4, 5..7, 10..13], set.ranges());
+        assert_eq!(9, set.len());
+        set.insert(4..5);
+        assert_eq!(&[0..7, 10..13], set.ranges());
+        set.insert(8..20);
+        assert_eq!(&[0..7, 8..20], set.ranges());
+        set.insert(-5..0);
+        set.insert(7..7);
+        assert_eq!("{-5..7, 8..20}", format!("{:?}", set));
+        set.remove(2..=3);
+        set.remove(6..9);
+        assert_eq!(&[-5..2, 4..6, 9..20], set.ranges());
+        set.remove(..5);
+        assert_eq!(&[5..6, 9..20], set.ranges());
+        assert_eq!(Some(5..20), set.span());
+        set.remove(..);
+        assert!(set.is_empty() && set.span().is_none());
+
+        let mut all = IntervalSet::<u8>::new();
+        all.insert(..);
+        assert_eq!(255, all.len());
+        assert!(all.contains(0) && !all.contains(255));
+    }
+
+    #[test]
+    fn test_queries() {
+        let set: IntervalSet<i64> = [-2..=2, 6..=8].into_iter().collect();
+        assert!(set.contains(-2) && set.contains(2) && !set.contains(3) && !set.contains(9));
+        assert!(set.contains_range(-1..=2) && set.contains_range(6..6));
+        assert!(!set.contains_range(0..7) && !set.contains_range(8..=9));
+        assert!(set.overlaps(2..=5) && set.overlaps(..-1) && !set.overlaps(3..6));
+        assert!(!set.overlaps(4..4));
+        assert_eq!(
+            vec![-5..-2, 3..6, 9..12],
+            set.gaps(-5..12).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![3..6], set.gaps(0..=7).collect::<Vec<_>>());
+        assert_eq!(0, set.gaps(6..8).count());
+        assert_eq!(vec![20..30], set.gaps(20..30).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_set_ops() {
+        let a: IntervalSet<u32> = [0..5, 10..15, 20..25].into_iter().collect();
+        let b: IntervalSet<u32> = [3..12, 15..17, 24..30].into_iter().collect();
+        assert_eq!(&[0..17, 20..30], a.union(&b).ranges());
+        assert_eq!(&[3..5, 10..12, 24..25], a.intersection(&b).ranges());
+        assert_eq!(&[0..3, 12..15, 20..24], a.difference(&b).ranges());
+        // Checks each against a set of the values.
+        let values =
+            |set: &IntervalSet<u32>| (0..40).filter(|&x| set.contains(x)).collect::<Vec<_>>();
+        let (va, vb) = (values(&a), values(&b));
+        let union: Vec<_> = (0..40)
+            .filter(|x| va.contains(x) || vb.contains(x))
+            .collect();
+        assert_eq!(union, values(&a.union(&b)));
+        let both: Vec<_> = va.iter().copied().filter(|x| vb.contains(x)).collect();
+        assert_eq!(both, values(&a.intersection(&b)));
+        assert_eq!(
+            a.len() + b.len(),
+            a.union(&b).len() + a.intersection(&b).len()
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Helpers shared by the Rust solutions. Add it to a solution's `Cargo.toml` with
+//!
+//! ```toml
+//! [dependencies]
+//! aoc-util = { path = "../../../../../lib/aoc-util" }
+//! ```
+//!
+//! It only depends on `std`, so solutions still build offline in the docker image.
+
+pub mod bitset;
+pub mod cycle;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod interval_set;
+pub mod parse;
+pub mod search;
+pub mod union_find;
@@ -0,0 +1,153 @@
+//! Allocation-free integer parsing. The input is assumed to be well-formed: digits beyond the
+//! range of the type wrap around instead of failing.
+
+use std::marker::PhantomData;
+
+/// The primitive integers.
+pub trait Integer: Copy {
+    /// Whether a `-` before the digits negates them.
+    const SIGNED: bool;
+    const ZERO: Self;
+
+    /// `self * 10 + digit`.
+    fn push_digit(self, digit: u8) -> Self;
+
+    fn negate(self) -> Self;
+}
+
+macro_rules! integer {
+    ($signed:literal => $($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const SIGNED: bool = $signed;
+                const ZERO: Self = 0;
+
+                #[inline(always)]
+                fn push_digit(self, digit: u8) -> Self {
+                    self.wrapping_mul(10).wrapping_add(digit as Self)
+                }
+
+                #[inline(always)]
+                fn negate(self) -> Self {
+                    self.wrapping_neg()
+                }
+            }
+        )*
+    };
+}
+integer!(false => u8, u16, u32, u64, u128, usize);
+integer!(true => i8, i16, i32, i64, i128, isize);
+
+/// Parses `input` as a whole, e.g. a token from `split(',')`. Signed types accept a leading
+/// `-` or `+`.
+pub fn parse<T: Integer>(input: &(impl AsRef<[u8]> + ?Sized)) -> Option<T> {
+    let bytes = input.as_ref();
+    let (negative, digits) = match bytes {
+        [b'-', digits @ ..] if T::SIGNED => (true, digits),
+        [b'+', digits @ ..] if T::SIGNED => (false, digits),
+        digits => (false, digits),
+    };
+    if digits.is_empty() {
+        return None;
+    }
+    let mut n = T::ZERO;
+    for &b in digits {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        n = n.push_digit(b - b'0');
+    }
+    Some(if negative { n.negate() } else { n })
+}
+
+/// Every integer in the input, in order, skipping whatever is between them. For signed types a
+/// `-` right before the digits negates them, unless it directly follows another number (the
+/// range `2-4`).
+#[derive(Debug, Clone)]
+pub struct Ints<'a, T> {
+    rest: &'a [u8],
+    _integer: PhantomData<T>,
+}
+
+impl<T: Integer> Iterator for Ints<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        let mut bytes = self.rest.iter();
+        let mut prev = 0;
+        let first = loop {
+            let &b = bytes.next()?;
+            let digit = b.wrapping_sub(b'0');
+            if digit < 10 {
+                break digit;
+            }
+            prev = b;
+        };
+        let mut n = T::ZERO.push_digit(first);
+        // The byte after the number is consumed too, so a `-` there isn't a sign.
+        for &b in bytes.by_ref() {
+            let digit = b.wrapping_sub(b'0');
+            if digit >= 10 {
+                break;
+            }
+            n = n.push_digit(digit);
+        }
+        self.rest = bytes.as_slice();
+        Some(if T::SIGNED && prev == b'-' {
+            n.negate()
+        } else {
+            n
+        })
+    }
+}
+
+/// See [`Ints`].
+pub fn ints<T: Integer>(input: &(impl AsRef<[u8]> + ?Sized)) -> Ints<'_, T> {
+    Ints {
+        rest: input.as_ref(),
+        _integer: PhantomData,
+    }
+}
+
+/// The first `N` integers of the input, e.g. `let [x1, y1, x2, y2] = ints_array(line)?` for
+/// `0,9 -> 5,9`. `None` if there are fewer.
+pub fn ints_array<T: Integer, const N: usize>(
+    input: &(impl AsRef<[u8]> + ?Sized),
+) -> Option<[T; N]> {
+    let mut ints = ints(input);
+    let mut array = [T::ZERO; N];
+    for slot in &mut array {
+        *slot = ints.next()?;
+    }
+    Some(array)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Some(1234u16), parse("1234"));
+        assert_eq!(Some(-56i32), parse("-56"));
+        assert_eq!(Some(7i64), parse(b"+7"));
+        assert_eq!(None, parse::<u32>("-56"));
+        assert_eq!(None, parse::<i32>("-"));
+        assert_eq!(None, parse::<u8>("1 "));
+        assert_eq!(None, parse::<u8>(""));
+        assert_eq!(Some(i64::MIN), parse("-9223372036854775808"));
+    }
+
+    #[test]
+    fn test_ints() {
+        let line = "Sensor at x=2, y=-18: closest beacon is at x=-2, y=15";
+        assert_eq!(vec![2, -18, -2, 15], ints::<i32>(line).collect::<Vec<_>>());
+        assert_eq!(vec![2u32, 18, 2, 15], ints(line).collect::<Vec<_>>());
+        assert_eq!(vec![2u8, 4, 6, 8], ints("2-4,6-8").collect::<Vec<_>>());
+        assert_eq!(vec![2i8, 4, -6, 8], ints("2-4,-6-8").collect::<Vec<_>>());
+        assert_eq!(Some([0usize, 9, 5, 9]), ints_array("0,9 -> 5,9"));
+        assert_eq!(None, ints_array::<usize, 5>("0,9 -> 5,9"));
+        assert_eq!(0, ints::<u64>("no numbers").count());
+    }
+}
@@ -0,0 +1,360 @@
+//! Shortest paths: BFS, Dijkstra and A*, generic over the state, its neighbors and the
+//! heuristic. Every search keeps the parent of each state, so the path can be reconstructed.
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Edge weights and distances.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+impl<T: Copy + Ord + Add<Output = T> + Default> Cost for T {}
+
+/// The cheapest way found to a goal.
+#[derive(Debug, Clone)]
+pub struct Found<S, C> {
+    pub cost: C,
+    goal: usize,
+    nodes: Nodes<S, C>,
+}
+
+impl<S, C> Found<S, C> {
+    pub fn goal(&self) -> &S {
+        &self.nodes.states[self.goal].0
+    }
+
+    /// The states from the start to the goal, both included.
+    pub fn path(&self) -> Vec<&S> {
+        let mut path: Vec<_> = std::iter::successors(Some(self.goal), |&i| self.nodes.parents[i])
+            .map(|i| &self.nodes.states[i].0)
+            .collect();
+        path.reverse();
+        path
+    }
+
+    /// How many states were reached, for comparing heuristics.
+    pub fn reached(&self) -> usize {
+        self.nodes.states.len()
+    }
+}
+
+/// Every state reached, numbered in order, with its best cost so far and its parent.
+#[derive(Debug, Clone)]
+struct Nodes<S, C> {
+    index: HashMap<S, usize>,
+    states: Vec<(S, C)>,
+    parents: Vec<Option<usize>>,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> Nodes<S, C> {
+    fn new(start: S) -> Self {
+        Self {
+            index: HashMap::from([(start.clone(), 0)]),
+            states: vec![(start, C::default())],
+            parents: vec![None],
+        }
+    }
+
+    /// Records `state` reached from `parent` at `cost`. Returns its number, if that's the
+    /// cheapest way to it so far.
+    fn relax(&mut self, state: S, cost: C, parent: usize) -> Option<usize> {
+        match self.index.entry(state) {
+            Entry::Occupied(entry) => {
+                let i = *entry.get();
+                if cost >= self.states[i].1 {
+                    return None;
+                }
+                self.states[i].1 = cost;
+                self.parents[i] = Some(parent);
+                Some(i)
+            }
+            Entry::Vacant(entry) => {
+                let i = self.states.len();
+                self.states.push((entry.key().clone(), cost));
+                self.parents.push(Some(parent));
+                entry.insert(i);
+                Some(i)
+            }
+        }
+    }
+
+    fn found(self, goal: usize) -> Found<S, C> {
+        Found {
+            cost: self.states[goal].1,
+            goal,
+            nodes: self,
+        }
+    }
+}
+
+/// Breadth-first search, every edge costing 1. The goal is checked when a state is reached, so
+/// `start` itself only counts if it's a goal.
+pub fn bfs<S, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut nodes = Nodes::new(start);
+    if is_goal(&nodes.states[0].0) {
+        return Some(nodes.found(0));
+    }
+    let mut queue = VecDeque::from([0]);
+    while let Some(i) = queue.pop_front() {
+        let cost = nodes.states[i].1 + 1;
+        for next in neighbors(&nodes.states[i].0) {
+            if nodes.index.contains_key(&next) {
+                continue;
+            }
+            let j = nodes.relax(next, cost, i).unwrap();
+            if is_goal(&nodes.states[j].0) {
+                return Some(nodes.found(j));
+            }
+            queue.push_back(j);
+        }
+    }
+    None
+}
+
+/// Dijkstra's algorithm: the cheapest path to a state that `is_goal`. `neighbors` gives the
+/// next states with the cost of getting there.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    neighbors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, neighbors, |_| C::default(), is_goal)
+}
+
+/// A*: Dijkstra's algorithm visiting states in order of cost plus `heuristic`. The path is
+/// the cheapest if the heuristic never overestimates the remaining cost.
+pub fn astar<S, C, I>(
+    start: S,
+    neighbors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    search(start, BinaryHeap::new(), neighbors, heuristic, is_goal)
+}
+
+/// A* on a [`BucketQueue`], for small integer costs like 2021/15's risk levels of 1 to 9.
+/// Faster than [`astar`] when the costs are dense. A zero heuristic makes it Dijkstra's
+/// algorithm (Dial's algorithm).
+pub fn astar_buckets<S, I>(
+    start: S,
+    neighbors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> usize,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    search(start, BucketQueue::new(), neighbors, heuristic, is_goal)
+}
+
+/// A min priority queue of `(cost, state number)`.
+trait Frontier<C> {
+    fn push(&mut self, priority: C, item: (C, usize));
+    fn pop(&mut self) -> Option<(C, usize)>;
+}
+
+impl<C: Ord> Frontier<C> for BinaryHeap<Reverse<(C, C, usize)>> {
+    fn push(&mut self, priority: C, (cost, i): (C, usize)) {
+        BinaryHeap::push(self, Reverse((priority, cost, i)));
+    }
+
+    fn pop(&mut self) -> Option<(C, usize)> {
+        BinaryHeap::pop(self).map(|Reverse((_, cost, i))| (cost, i))
+    }
+}
+
+impl Frontier<usize> for BucketQueue<(usize, usize)> {
+    fn push(&mut self, priority: usize, item: (usize, usize)) {
+        BucketQueue::push(self, priority, item);
+    }
+
+    fn pop(&mut self) -> Option<(usize, usize)> {
+        BucketQueue::pop(self).map(|(_, item)| item)
+    }
+}
+
+fn search<S, C, I>(
+    start: S,
+    mut frontier: impl Frontier<C>,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut nodes = Nodes::new(start);
+    frontier.push(heuristic(&nodes.states[0].0), (C::default(), 0));
+    while let Some((cost, i)) = frontier.pop() {
+        let (state, best) = &nodes.states[i];
+        // Stale: the state was reached more cheaply since this was pushed.
+        if cost > *best {
+            continue;
+        }
+        if is_goal(state) {
+            return Some(nodes.found(i));
+        }
+        for (next, edge) in neighbors(state) {
+            let cost = cost + edge;
+            if let Some(j) = nodes.relax(next, cost, i) {
+                let priority = cost + heuristic(&nodes.states[j].0);
+                frontier.push(priority, (cost, j));
+            }
+        }
+    }
+    None
+}
+
+/// A min priority queue with a bucket per integer priority. Pushing and popping are O(1)
+/// amortized while priorities stay close to the smallest one, as in Dijkstra's algorithm with
+/// small edge costs.
+#[derive(Debug, Clone)]
+pub struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    /// No bucket before this one has items.
+    min: usize,
+    len: usize,
+}
+
+impl<T> Default for BucketQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new() -> Self {
+        Self {
+            buckets: Vec::new(),
+            min: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, priority: usize, item: T) {
+        if self.buckets.len() <= priority {
+            self.buckets.resize_with(priority + 1, Vec::new);
+        }
+        self.buckets[priority].push(item);
+        self.min = self.min.min(priority);
+        self.len += 1;
+    }
+
+    /// An item with the smallest priority, last in first out among equals.
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        if self.is_empty() {
+            return None;
+        }
+        while self.buckets[self.min].is_empty() {
+            self.min += 1;
+        }
+        self.len -= 1;
+        Some((self.min, self.buckets[self.min].pop().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::Grid;
+
+    /// 2021/15's example.
+    const RISKS: &str = "1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581
+";
+
+    #[test]
+    fn test_dijkstra_astar() {
+        let grid = Grid::parse(RISKS, |b| (b - b'0') as usize);
+        let goal = (grid.height() - 1, grid.width() - 1);
+        let neighbors = |&pos: &(usize, usize)| grid.neighbors4(pos).map(|next| (next, grid[next]));
+        let heuristic = |&(r, c): &(usize, usize)| goal.0 - r + goal.1 - c;
+
+        let found = dijkstra((0, 0), neighbors, |&pos| pos == goal).unwrap();
+        assert_eq!(40, found.cost);
+        assert_eq!(&goal, found.goal());
+        let path = found.path();
+        assert_eq!((&(0, 0), &goal), (path[0], path[path.len() - 1]));
+        assert_eq!(40, path[1..].iter().map(|&&pos| grid[pos]).sum::<usize>());
+
+        let astar = astar((0, 0), neighbors, heuristic, |&pos| pos == goal).unwrap();
+        assert_eq!(40, astar.cost);
+        assert!(astar.reached() <= found.reached());
+        let buckets = astar_buckets((0, 0), neighbors, heuristic, |&pos| pos == goal).unwrap();
+        assert_eq!(40, buckets.cost);
+        assert_eq!(found.path().len(), buckets.path().len());
+
+        assert!(dijkstra((0, 0), neighbors, |_| false).is_none());
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = Grid::parse("S.#\n.##\n..E\n", |b| b);
+        let found = bfs(
+            (0, 0),
+            |&pos| grid.neighbors4(pos).filter(|&next| grid[next] != b'#'),
+            |&pos| grid[pos] == b'E',
+        )
+        .unwrap();
+        assert_eq!(4, found.cost);
+        assert_eq!(
+            vec![&(0, 0), &(1, 0), &(2, 0), &(2, 1), &(2, 2)],
+            found.path()
+        );
+        assert_eq!(0, bfs(1, |_| [], |&n| n == 1).unwrap().cost);
+        assert!(bfs(1, |&n| (n < 10).then_some(n + 1), |&n| n == 20).is_none());
+    }
+
+    #[test]
+    fn test_bucket_queue() {
+        let mut queue = BucketQueue::new();
+        queue.push(3, 'a');
+        queue.push(1, 'b');
+        queue.push(3, 'c');
+        assert_eq!(Some((1, 'b')), queue.pop());
+        queue.push(0, 'd');
+        assert_eq!(3, queue.len());
+        assert_eq!(Some((0, 'd')), queue.pop());
+        assert_eq!(Some((3, 'c')), queue.pop());
+        assert_eq!(Some((3, 'a')), queue.pop());
+        assert_eq!(None, queue.pop());
+    }
+}
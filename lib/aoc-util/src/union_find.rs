@@ -0,0 +1,248 @@
+//! Disjoint sets with union by size and path compression, over indices (`UnionFind`) or any
+//! hashable key (`SparseUnionFind`).
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Disjoint sets of `0..len`, each starting on its own.
+#[derive(Debug, Clone, Default)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    /// Only meaningful for roots.
+    sizes: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            count: len,
+        }
+    }
+
+    /// How many elements, in all sets.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Adds an element in a set of its own and returns it.
+    pub fn push(&mut self) -> usize {
+        let x = self.parents.len();
+        self.parents.push(x);
+        self.sizes.push(1);
+        self.count += 1;
+        x
+    }
+
+    /// The root of the set containing `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        // Point everything on the way straight at the root.
+        let mut x = x;
+        while self.parents[x] != root {
+            x = std::mem::replace(&mut self.parents[x], root);
+        }
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`. Returns whether they were apart.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        self.count -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Size of the set containing `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.sizes[root]
+    }
+
+    /// How many sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The root and size of each set.
+    pub fn components(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.len())
+            .filter(|&x| self.parents[x] == x)
+            .map(|x| (x, self.sizes[x]))
+    }
+
+    /// The elements of each set, in order of their smallest element.
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut group_of = HashMap::with_capacity(self.count);
+        let mut groups: Vec<Vec<usize>> = Vec::with_capacity(self.count);
+        for x in 0..self.len() {
+            let root = self.find(x);
+            let group = *group_of.entry(root).or_insert_with(|| {
+                groups.push(Vec::with_capacity(self.sizes[root]));
+                groups.len() - 1
+            });
+            groups[group].push(x);
+        }
+        groups
+    }
+}
+
+/// Disjoint sets of keys, which are added the first time they're used.
+#[derive(Debug, Clone)]
+pub struct SparseUnionFind<K> {
+    index: HashMap<K, usize>,
+    keys: Vec<K>,
+    sets: UnionFind,
+}
+
+impl<K> Default for SparseUnionFind<K> {
+    fn default() -> Self {
+        Self {
+            index: HashMap::new(),
+            keys: Vec::new(),
+            sets: UnionFind::default(),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash> SparseUnionFind<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How many keys, in all sets.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// Adds `key` in a set of its own, if it's new.
+    pub fn insert(&mut self, key: K) {
+        self.index_of(key);
+    }
+
+    fn index_of(&mut self, key: K) -> usize {
+        if let Some(&i) = self.index.get(&key) {
+            return i;
+        }
+        let i = self.sets.push();
+        self.keys.push(key.clone());
+        self.index.insert(key, i);
+        i
+    }
+
+    /// The representative of the set containing `key`.
+    pub fn find(&mut self, key: K) -> &K {
+        let i = self.index_of(key);
+        &self.keys[self.sets.find(i)]
+    }
+
+    /// Merges the sets containing `a` and `b`. Returns whether they were apart.
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let (a, b) = (self.index_of(a), self.index_of(b));
+        self.sets.union(a, b)
+    }
+
+    pub fn same(&mut self, a: K, b: K) -> bool {
+        let (a, b) = (self.index_of(a), self.index_of(b));
+        self.sets.same(a, b)
+    }
+
+    /// Size of the set containing `key`.
+    pub fn size(&mut self, key: K) -> usize {
+        let i = self.index_of(key);
+        self.sets.size(i)
+    }
+
+    /// How many sets.
+    pub fn count(&self) -> usize {
+        self.sets.count()
+    }
+
+    /// The representative and size of each set.
+    pub fn components(&self) -> impl Iterator<Item = (&K, usize)> + '_ {
+        self.sets
+            .components()
+            .map(|(root, size)| (&self.keys[root], size))
+    }
+
+    /// The keys of each set, in the order they were added.
+    pub fn groups(&mut self) -> Vec<Vec<&K>> {
+        self.sets
+            .groups()
+            .into_iter()
+            .map(|group| group.into_iter().map(|i| &self.keys[i]).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.same(0, 3));
+        assert!(!sets.same(0, 4));
+        assert_eq!(4, sets.size(2));
+        assert_eq!(3, sets.count());
+        assert_eq!(6, sets.push());
+        assert!(sets.union(6, 5));
+        assert_eq!(
+            vec![4, 1, 2],
+            sets.components().map(|(_, size)| size).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![vec![0, 1, 2, 3], vec![4], vec![5, 6]], sets.groups());
+    }
+
+    #[test]
+    fn test_sparse_union_find() {
+        let mut sets = SparseUnionFind::new();
+        sets.union("a", "b");
+        sets.union("c", "d");
+        sets.insert("e");
+        assert!(!sets.same("a", "c"));
+        sets.union("d", "b");
+        assert!(sets.same("a", "c"));
+        assert_eq!(sets.find("a").to_owned(), sets.find("d").to_owned());
+        assert_eq!(4, sets.size("c"));
+        assert_eq!(1, sets.size("f"));
+        assert_eq!((6, 3), (sets.len(), sets.count()));
+        assert_eq!(
+            vec![vec![&"a", &"b", &"c", &"d"], vec![&"e"], vec![&"f"]],
+            sets.groups()
+        );
+    }
+}
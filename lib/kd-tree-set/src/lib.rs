@@ -0,0 +1,536 @@
+//! Sets of points in a K-dimensional integer grid, stored as a k-d tree of boxes. Setting a
+//! whole box is cheap however big it is, as in 2021/22's reactor reboot. Add it to a
+//! solution's `Cargo.toml` with
+//!
+//! ```toml
+//! [dependencies]
+//! kd-tree-set = { path = "../../../../../lib/kd-tree-set" }
+//! ```
+//!
+//! The `serde` feature adds `Serialize` and `Deserialize`, but serde isn't vendored in the
+//! docker image, so solutions can't use it.
+
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// An integer coordinate type.
+pub trait Coord: Copy + Ord + Debug {
+    const MIN: Self;
+    const MAX: Self;
+
+    fn to_i128(self) -> i128;
+}
+
+macro_rules! coord {
+    ($($t:ty),*) => {
+        $(
+            impl Coord for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+            }
+        )*
+    };
+}
+
+coord!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// `min..max`, half-open.
+#[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Range<T> {
+    pub min: T,
+    pub max: T,
+}
+impl<T: Coord> Range<T> {
+    /// `T::MIN..T::MAX`: every coordinate but `T::MAX` itself.
+    pub const ALL: Self = Self {
+        min: T::MIN,
+        max: T::MAX,
+    };
+
+    pub fn len(&self) -> u128 {
+        (self.max.to_i128() - self.min.to_i128()).max(0) as u128
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.max <= self.min
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.min <= x && x < self.max
+    }
+}
+
+/// A box: a range on each axis.
+pub type Cuboid<T, const K: usize> = [Range<T>; K];
+
+/// Number of points in a box, saturating at `u128::MAX`.
+pub fn volume<T: Coord, const K: usize>(cuboid: &Cuboid<T, K>) -> u128 {
+    cuboid
+        .iter()
+        .fold(1_u128, |volume, range| volume.saturating_mul(range.len()))
+}
+
+/// A set of points with coordinates of type `T` in `K` dimensions.
+#[derive(Debug, Clone)]
+pub struct KdTreeSet<T, const K: usize> {
+    root: Node<T>,
+    dimensions: PhantomData<[(); K]>,
+}
+impl<T: Coord, const K: usize> Default for KdTreeSet<T, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Coord, const K: usize> KdTreeSet<T, K> {
+    const INFINITE_CUBOID: Cuboid<T, K> = [Range::ALL; K];
+
+    /// The empty set.
+    pub fn new() -> Self {
+        Self::with_value(false)
+    }
+    /// The empty set, or every point if `value`.
+    pub fn with_value(value: bool) -> Self {
+        Self {
+            root: Node::Value(value),
+            dimensions: PhantomData,
+        }
+    }
+
+    /// Number of points in the set, saturating at `u128::MAX`.
+    pub fn volume(&self) -> u128 {
+        self.boxes()
+            .fold(0_u128, |sum, cuboid| sum.saturating_add(volume(&cuboid)))
+    }
+    pub fn is_empty(&self) -> bool {
+        self.boxes().next().is_none()
+    }
+    pub fn contains(&self, point: [T; K]) -> bool {
+        // Outside the infinite cuboid.
+        if point.contains(&T::MAX) {
+            return false;
+        }
+        let mut node = &self.root;
+        loop {
+            match node {
+                &Node::Value(value) => return value,
+                Node::Parent {
+                    axis,
+                    split,
+                    children,
+                } => {
+                    node = if point[*axis] < *split {
+                        &children.0
+                    } else {
+                        &children.1
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds every point of `value_cuboid` if `value`, otherwise removes them.
+    pub fn set_cuboid(&mut self, value_cuboid: Cuboid<T, K>, value: bool) {
+        if value_cuboid.iter().any(Range::is_empty) {
+            return;
+        }
+        self.root
+            .set_cuboid(Self::INFINITE_CUBOID, value_cuboid, value);
+    }
+
+    /// Disjoint boxes covering exactly the set, in no particular order.
+    pub fn boxes(&self) -> Boxes<'_, T, K> {
+        Boxes {
+            stack: vec![(&self.root, Self::INFINITE_CUBOID)],
+        }
+    }
+
+    /// Every point not in the set.
+    pub fn complement(&self) -> Self {
+        Self {
+            root: self.root.complement(),
+            dimensions: PhantomData,
+        }
+    }
+    pub fn union(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for cuboid in other.boxes() {
+            out.set_cuboid(cuboid, true);
+        }
+        out
+    }
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.difference(&other.complement())
+    }
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for cuboid in other.boxes() {
+            out.set_cuboid(cuboid, false);
+        }
+        out
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize, const K: usize> Serialize for KdTreeSet<T, K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.root.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Coord + Deserialize<'de>, const K: usize> Deserialize<'de> for KdTreeSet<T, K> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let root = Node::deserialize(deserializer)?;
+        if !root.axes_below(K) {
+            return Err(serde::de::Error::custom(format!(
+                "axis out of range for {} dimensions",
+                K
+            )));
+        }
+        Ok(Self {
+            root,
+            dimensions: PhantomData,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+enum Node<T> {
+    Value(bool),
+    Parent {
+        // This axis this node splits along.
+        axis: usize,
+        // The coordinate to split at. This is the included in the SECOND (high) child.
+        // I.e. exclusive upper bound for low, inclusive lower bound for high.
+        split: T,
+        // The low and high children.
+        children: Box<(Node<T>, Node<T>)>,
+    },
+}
+impl<T: Coord> Node<T> {
+    fn set_cuboid<const K: usize>(
+        &mut self,
+        node_cuboid: Cuboid<T, K>,
+        value_cuboid: Cuboid<T, K>,
+        value: bool,
+    ) {
+        match self {
+            &mut Self::Value(old_value) => {
+                if old_value == value {
+                    return;
+                }
+                for axis in 0..K {
+                    let node_range = node_cuboid[axis];
+                    let value_range = value_cuboid[axis];
+
+                    // Check if: CURRENT and VALUE do not overlap.
+                    let no_overlap =
+                        node_range.max <= value_range.min || value_range.max <= node_range.min;
+                    if no_overlap {
+                        return;
+                    }
+
+                    // Check if: VALUE half-intersects CURRENT on the low-end.
+                    let intersects_low =
+                        node_range.min < value_range.max && value_range.max < node_range.max;
+                    if intersects_low {
+                        let mut children =
+                            Box::new((Self::Value(old_value), Self::Value(old_value)));
+                        let split = value_range.max;
+
+                        let mut child_cuboid = node_cuboid;
+                        child_cuboid[axis].max = split;
+                        children.0.set_cuboid(child_cuboid, value_cuboid, value);
+
+                        *self = Self::Parent {
+                            axis,
+                            split,
+                            children,
+                        };
+                        return;
+                    }
+                    // Check if: VALUE half-intersects CURRENT on the high-end.
+                    let intersects_hih =
+                        node_range.min < value_range.min && value_range.min < node_range.max;
+                    if intersects_hih {
+                        let mut children =
+                            Box::new((Self::Value(old_value), Self::Value(old_value)));
+                        let split = value_range.min;
+
+                        let mut child_cuboid = node_cuboid;
+                        child_cuboid[axis].min = split;
+                        children.1.set_cuboid(child_cuboid, value_cuboid, value);
+
+                        *self = Self::Parent {
+                            axis,
+                            split,
+                            children,
+                        };
+                        return;
+                    }
+
+                    // Else: VALUE completely contains CURRENT. Continue.
+                }
+
+                // VALUE completely contains CURRENT in all axis. Update value.
+                *self = Self::Value(value);
+            }
+            Self::Parent {
+                axis,
+                split,
+                children,
+            } => {
+                {
+                    let mut low_child_cuboid = node_cuboid;
+                    low_child_cuboid[*axis].max = *split;
+                    children.0.set_cuboid(low_child_cuboid, value_cuboid, value);
+                }
+                {
+                    let mut hih_child_cuboid = node_cuboid;
+                    hih_child_cuboid[*axis].min = *split;
+                    children.1.set_cuboid(hih_child_cuboid, value_cuboid, value);
+                }
+                // Merge children that ended up the same, e.g. after removing what was added.
+                if let (Self::Value(low), Self::Value(hih)) = &**children {
+                    if low == hih {
+                        *self = Self::Value(*low);
+                    }
+                }
+            }
+        }
+    }
+
+    fn complement(&self) -> Self {
+        match self {
+            &Self::Value(value) => Self::Value(!value),
+            Self::Parent {
+                axis,
+                split,
+                children,
+            } => Self::Parent {
+                axis: *axis,
+                split: *split,
+                children: Box::new((children.0.complement(), children.1.complement())),
+            },
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    fn axes_below(&self, k: usize) -> bool {
+        match self {
+            Self::Value(_) => true,
+            Self::Parent { axis, children, .. } => {
+                *axis < k && children.0.axes_below(k) && children.1.axes_below(k)
+            }
+        }
+    }
+}
+
+/// The boxes of a [`KdTreeSet`], see [`KdTreeSet::boxes`].
+pub struct Boxes<'a, T, const K: usize> {
+    stack: Vec<(&'a Node<T>, Cuboid<T, K>)>,
+}
+impl<T: Coord, const K: usize> Iterator for Boxes<'_, T, K> {
+    type Item = Cuboid<T, K>;
+
+    fn next(&mut self) -> Option<Cuboid<T, K>> {
+        while let Some((node, node_cuboid)) = self.stack.pop() {
+            match node {
+                Node::Value(true) => return Some(node_cuboid),
+                Node::Value(false) => {}
+                Node::Parent {
+                    axis,
+                    split,
+                    children,
+                } => {
+                    let mut low_child_cuboid = node_cuboid;
+                    low_child_cuboid[*axis].max = *split;
+                    let mut hih_child_cuboid = node_cuboid;
+                    hih_child_cuboid[*axis].min = *split;
+                    self.stack.push((&children.1, hih_child_cuboid));
+                    self.stack.push((&children.0, low_child_cuboid));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_overlap_none() {
+        let mut kd_tree = KdTreeSet::new();
+        kd_tree.set_cuboid([Range { min: 0, max: 4 }; 2], true);
+        kd_tree.set_cuboid([Range { min: 4, max: 8 }; 2], true);
+        assert_eq!(32, kd_tree.volume());
+    }
+
+    #[test]
+    fn test_overlap_corner() {
+        let mut kd_tree = KdTreeSet::new();
+        kd_tree.set_cuboid([Range { min: 0, max: 4 }; 2], true);
+        kd_tree.set_cuboid([Range { min: 2, max: 6 }; 2], true);
+        assert_eq!(28, kd_tree.volume());
+    }
+
+    #[test]
+    fn test_overlap_edge_aligned() {
+        let mut kd_tree = KdTreeSet::new();
+        kd_tree.set_cuboid([Range { min: 0, max: 4 }; 2], true);
+        kd_tree.set_cuboid([Range { min: 0, max: 4 }, Range { min: 2, max: 6 }], true);
+        assert_eq!(16 + 16 - 8, kd_tree.volume());
+    }
+
+    #[test]
+    fn test_overlap_edge_inside() {
+        let mut kd_tree = KdTreeSet::new();
+        kd_tree.set_cuboid([Range { min: 0, max: 8 }; 2], true);
+        kd_tree.set_cuboid([Range { min: 4, max: 12 }, Range { min: 2, max: 6 }], true);
+        assert_eq!(64 + 16, kd_tree.volume());
+    }
+
+    #[test]
+    fn test_subtract_edge_aligned() {
+        let mut kd_tree = KdTreeSet::new();
+        kd_tree.set_cuboid([Range { min: 0, max: 4 }; 2], true);
+        kd_tree.set_cuboid([Range { min: 0, max: 4 }, Range { min: 2, max: 6 }], false);
+        assert_eq!(16 - 8, kd_tree.volume());
+    }
+
+    #[test]
+    fn test_subtract_edge_inside() {
+        let mut kd_tree = KdTreeSet::new();
+        kd_tree.set_cuboid([Range { min: 0, max: 8 }; 2], true);
+        kd_tree.set_cuboid([Range { min: 4, max: 12 }, Range { min: 2, max: 6 }], false);
+        assert_eq!(64 - 16, kd_tree.volume());
+    }
+
+    #[test]
+    fn test_unbounded() {
+        let everything = KdTreeSet::<u8, 2>::with_value(true);
+        assert_eq!(255 * 255, everything.volume());
+        assert!(!everything.contains([0, u8::MAX]));
+        let mut kd_tree = everything.clone();
+        kd_tree.set_cuboid([Range { min: 0, max: 255 }; 2], false);
+        assert!(kd_tree.is_empty());
+        assert_eq!(u128::MAX, KdTreeSet::<i64, 3>::with_value(true).volume());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut kd_tree = KdTreeSet::<i32, 3>::new();
+        kd_tree.set_cuboid([Range { min: -5, max: 5 }; 3], true);
+        let json = serde_json::to_string(&kd_tree).unwrap();
+        let back: KdTreeSet<i32, 3> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            kd_tree.boxes().collect::<Vec<_>>(),
+            back.boxes().collect::<Vec<_>>()
+        );
+        assert!(serde_json::from_str::<KdTreeSet<i32, 2>>(&json).is_err());
+    }
+
+    /// Coordinates `-SPAN..SPAN`, small enough to list every voxel.
+    const SPAN: i8 = 6;
+
+    type Voxels = BTreeSet<[i8; 3]>;
+
+    fn cuboid() -> impl Strategy<Value = Cuboid<i8, 3>> {
+        let range = (-SPAN..SPAN, -SPAN..=SPAN).prop_map(|(a, b)| Range {
+            min: a.min(b),
+            max: a.max(b),
+        });
+        [range.clone(), range.clone(), range]
+    }
+
+    fn ops() -> impl Strategy<Value = Vec<(Cuboid<i8, 3>, bool)>> {
+        prop::collection::vec((cuboid(), any::<bool>()), 0..12)
+    }
+
+    fn build(ops: &[(Cuboid<i8, 3>, bool)]) -> (KdTreeSet<i8, 3>, Voxels) {
+        let mut kd_tree = KdTreeSet::new();
+        let mut voxels = Voxels::new();
+        for &(cuboid, value) in ops {
+            kd_tree.set_cuboid(cuboid, value);
+            for x in cuboid[0].min..cuboid[0].max {
+                for y in cuboid[1].min..cuboid[1].max {
+                    for z in cuboid[2].min..cuboid[2].max {
+                        if value {
+                            voxels.insert([x, y, z]);
+                        } else {
+                            voxels.remove(&[x, y, z]);
+                        }
+                    }
+                }
+            }
+        }
+        (kd_tree, voxels)
+    }
+
+    /// The voxels of the boxes, checking that they don't overlap.
+    fn voxels(kd_tree: &KdTreeSet<i8, 3>) -> Voxels {
+        let mut voxels = Voxels::new();
+        for cuboid in kd_tree.boxes() {
+            for x in cuboid[0].min..cuboid[0].max {
+                for y in cuboid[1].min..cuboid[1].max {
+                    for z in cuboid[2].min..cuboid[2].max {
+                        assert!(voxels.insert([x, y, z]), "Boxes overlap.");
+                    }
+                }
+            }
+        }
+        voxels
+    }
+
+    proptest! {
+        #[test]
+        fn prop_matches_voxels(ops in ops()) {
+            let (kd_tree, expected) = build(&ops);
+            prop_assert_eq!(expected.len() as u128, kd_tree.volume());
+            prop_assert_eq!(&expected, &voxels(&kd_tree));
+            for x in -SPAN..=SPAN {
+                for y in -SPAN..=SPAN {
+                    for z in -SPAN..=SPAN {
+                        prop_assert_eq!(
+                            expected.contains(&[x, y, z]),
+                            kd_tree.contains([x, y, z])
+                        );
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn prop_set_ops(a in ops(), b in ops()) {
+            let (a, a_voxels) = build(&a);
+            let (b, b_voxels) = build(&b);
+            prop_assert_eq!(
+                a_voxels.union(&b_voxels).copied().collect::<Voxels>(),
+                voxels(&a.union(&b))
+            );
+            prop_assert_eq!(
+                a_voxels.intersection(&b_voxels).copied().collect::<Voxels>(),
+                voxels(&a.intersection(&b))
+            );
+            prop_assert_eq!(
+                a_voxels.difference(&b_voxels).copied().collect::<Voxels>(),
+                voxels(&a.difference(&b))
+            );
+            prop_assert!(a.intersection(&a.complement()).is_empty());
+        }
+    }
+}
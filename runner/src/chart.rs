@@ -0,0 +1,134 @@
+use std::fmt::Write;
+
+use crate::history::HistoryRow;
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 400.0;
+const MARGIN: f64 = 60.0;
+
+/// Renders the total runtime of each row as an SVG line chart, one point per run. Failed runs
+/// are drawn as red crosses and don't join the line.
+pub fn runtime_svg(title: &str, rows: &[HistoryRow]) -> String {
+    let max_ms = rows
+        .iter()
+        .map(|row| row.entry.total_ms)
+        .max()
+        .unwrap_or(0)
+        .max(1) as f64;
+    let plot_w = WIDTH - 2.0 * MARGIN;
+    let plot_h = HEIGHT - 2.0 * MARGIN;
+    let x = |i: usize| {
+        if rows.len() <= 1 {
+            MARGIN + plot_w / 2.0
+        } else {
+            MARGIN + plot_w * i as f64 / (rows.len() - 1) as f64
+        }
+    };
+    let y = |ms: u64| HEIGHT - MARGIN - plot_h * ms as f64 / max_ms;
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="monospace" font-size="12">"#,
+        w = WIDTH,
+        h = HEIGHT
+    )
+    .unwrap();
+    writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+    writeln!(
+        svg,
+        r#"<text x="{}" y="{}" text-anchor="middle" font-size="16">{}</text>"#,
+        WIDTH / 2.0,
+        MARGIN / 2.0,
+        escape(title)
+    )
+    .unwrap();
+
+    // Axes, with gridlines at quarters of the max.
+    writeln!(
+        svg,
+        r#"<path d="M{l},{t} V{b} H{r}" stroke="black" fill="none"/>"#,
+        l = MARGIN,
+        t = MARGIN,
+        b = HEIGHT - MARGIN,
+        r = WIDTH - MARGIN
+    )
+    .unwrap();
+    for q in 1..=4 {
+        let ms = (max_ms * q as f64 / 4.0).round() as u64;
+        writeln!(
+            svg,
+            r##"<line x1="{l}" x2="{r}" y1="{y:.1}" y2="{y:.1}" stroke="#ddd"/><text x="{tx}" y="{y:.1}" text-anchor="end" dominant-baseline="middle">{ms}ms</text>"##,
+            l = MARGIN,
+            r = WIDTH - MARGIN,
+            y = y(ms),
+            tx = MARGIN - 5.0,
+            ms = ms
+        )
+        .unwrap();
+    }
+    for (i, row) in rows.iter().enumerate() {
+        if i == 0 || i + 1 == rows.len() {
+            writeln!(
+                svg,
+                r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+                x(i),
+                HEIGHT - MARGIN + 20.0,
+                escape(&row.datetime)
+            )
+            .unwrap();
+        }
+    }
+
+    let points: Vec<_> = rows
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| row.entry.passed())
+        .map(|(i, row)| format!("{:.1},{:.1}", x(i), y(row.entry.total_ms)))
+        .collect();
+    if !points.is_empty() {
+        writeln!(
+            svg,
+            r##"<polyline points="{}" stroke="#1f77b4" stroke-width="2" fill="none"/>"##,
+            points.join(" ")
+        )
+        .unwrap();
+    }
+    for (i, row) in rows.iter().enumerate() {
+        let (cx, cy) = (x(i), y(row.entry.total_ms));
+        let label = format!(
+            "{} {} {}ms",
+            row.datetime,
+            row.commit.as_deref().map_or("", |c| &c[..c.len().min(7)]),
+            row.entry.total_ms
+        );
+        if row.entry.passed() {
+            writeln!(
+                svg,
+                r##"<circle cx="{:.1}" cy="{:.1}" r="3" fill="#1f77b4"><title>{}</title></circle>"##,
+                cx,
+                cy,
+                escape(&label)
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                svg,
+                r##"<path d="M{:.1},{:.1} l8,8 m0,-8 l-8,8" stroke="#d62728" stroke-width="2"><title>{} ❌</title></path>"##,
+                cx - 4.0,
+                cy - 4.0,
+                escape(&label)
+            )
+            .unwrap();
+        }
+    }
+    writeln!(svg, "</svg>").unwrap();
+    svg
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
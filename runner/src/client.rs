@@ -0,0 +1,199 @@
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::html;
+use crate::puzzle::Puzzle;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Fetches puzzle inputs and pages from adventofcode.com (or the mock server).
+pub struct Client {
+    base_url: String,
+    session: Option<String>,
+    agent: ureq::Agent,
+    /// Inputs are cached in `{cache_dir}/{username}/{year}/{day}.input`, as they never change.
+    cache_dir: PathBuf,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: Option<String>, cache_dir: PathBuf) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session,
+            agent: ureq::AgentBuilder::new()
+                .user_agent(concat!("aoc-runner/", env!("CARGO_PKG_VERSION")))
+                .build(),
+            cache_dir,
+        }
+    }
+
+    fn get(&self, path: &str) -> Result<String> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut request = self.agent.get(&url);
+        if let Some(session) = &self.session {
+            request = request.set("Cookie", &format!("session={}", session));
+        }
+        response_body("GET", &url, request.call())
+    }
+
+    fn post_form(&self, path: &str, form: &[(&str, &str)]) -> Result<String> {
+        let url = format!("{}{}", self.base_url, path);
+        let session = self
+            .session
+            .as_ref()
+            .ok_or_else(|| anyhow!("Submitting answers needs a session cookie."))?;
+        let request = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", session));
+        response_body("POST", &url, request.send_form(form))
+    }
+
+    /// Submits the answer of a part, returning the response page.
+    pub fn answer(&self, year: u32, day: u32, part: u32, answer: &str) -> Result<String> {
+        self.post_form(
+            &format!("/{}/day/{}/answer", year, day),
+            &[("level", &part.to_string()), ("answer", answer)],
+        )
+    }
+
+    /// The puzzle input of `username`, which the session must belong to. Only fetched the first
+    /// time, later calls read the cache.
+    pub fn input(&self, year: u32, day: u32, username: &str) -> Result<String> {
+        let cached = self
+            .cache_dir
+            .join(username)
+            .join(year.to_string())
+            .join(format!("{}.input", day));
+        if let Ok(input) = std::fs::read_to_string(&cached) {
+            return Ok(input);
+        }
+        if self.session.is_none() {
+            return Err(anyhow!("Fetching inputs needs a session cookie."));
+        }
+        let input = self.get(&format!("/{}/day/{}/input", year, day))?;
+        std::fs::create_dir_all(cached.parent().unwrap())?;
+        std::fs::write(&cached, &input)
+            .with_context(|| format!("Failed to write {}.", cached.display()))?;
+        Ok(input)
+    }
+
+    /// The puzzle description as README.md markdown. Not cached, as part two only shows up
+    /// once part one is solved.
+    pub fn puzzle(&self, year: u32, day: u32) -> Result<String> {
+        let page = self.get(&format!("/{}/day/{}", year, day))?;
+        html::puzzle_markdown(&page)
+            .ok_or_else(|| anyhow!("No puzzle description on the {}/{} page.", year, day))
+    }
+}
+
+/// The body of a successful response, or an error with the server's message.
+fn response_body(
+    method: &str,
+    url: &str,
+    response: Result<ureq::Response, ureq::Error>,
+) -> Result<String> {
+    match response {
+        Ok(response) => response
+            .into_string()
+            .with_context(|| format!("Failed to read {}.", url)),
+        Err(ureq::Error::Status(status, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            Err(anyhow!("{} {}: {} {}", method, url, status, body.trim()))
+        }
+        Err(err) => Err(err).with_context(|| format!("Failed to {} {}.", method, url)),
+    }
+}
+
+/// Writes `{year}/{day}/io/{username}.input` if it's missing, and `README.md` if it's missing
+/// or has fewer parts than the page. Returns the files written, relative to the root.
+pub fn fetch(
+    root: &Path,
+    client: &Client,
+    year: u32,
+    day: u32,
+    username: &str,
+) -> Result<Vec<PathBuf>> {
+    let day_dir = root.join(year.to_string()).join(day.to_string());
+    let mut written = Vec::new();
+
+    let input_path = day_dir.join("io").join(format!("{}.input", username));
+    if !input_path.exists() {
+        let input = client.input(year, day, username)?;
+        std::fs::create_dir_all(input_path.parent().unwrap())?;
+        std::fs::write(&input_path, input)
+            .with_context(|| format!("Failed to write {}.", input_path.display()))?;
+        written.push(input_path);
+    }
+
+    let markdown = client.puzzle(year, day)?;
+    let fetched = Puzzle::parse(year, day, &markdown)?;
+    let existing = Puzzle::load(&day_dir, year, day)?;
+    if existing.is_none_or(|existing| existing.parts.len() < fetched.parts.len()) {
+        let readme = day_dir.join("README.md");
+        std::fs::write(&readme, markdown)
+            .with_context(|| format!("Failed to write {}.", readme.display()))?;
+        written.push(readme);
+    }
+    Ok(written
+        .into_iter()
+        .map(|path| path.strip_prefix(root).unwrap_or(&path).to_owned())
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockServer;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn test_fetch() {
+        let dir = TempDir::new("client");
+        let fixtures = dir.join("fixtures");
+        let root = dir.join("root");
+        let page_dir = fixtures.join("2021/day/1");
+        std::fs::create_dir_all(&page_dir).unwrap();
+        std::fs::write(
+            page_dir.join("index.html"),
+            "<main><article class=\"day-desc\"><h2>--- Day 1: Sonar Sweep ---</h2>\
+             <p>For example:</p><pre><code>199\n200\n</code></pre></article></main>",
+        )
+        .unwrap();
+        std::fs::write(page_dir.join("input"), "1\n2\n").unwrap();
+        let server = MockServer::start(fixtures, 0, std::time::Duration::ZERO).unwrap();
+
+        let anonymous = Client::new(&server.url(), None, dir.join("cache"));
+        let no_session = fetch(&root, &anonymous, 2021, 1, "someone");
+        let client = Client::new(&server.url(), Some("secret".to_owned()), dir.join("cache"));
+        let first = fetch(&root, &client, 2021, 1, "someone").unwrap();
+        let input = std::fs::read_to_string(root.join("2021/1/io/someone.input")).unwrap();
+        let readme = Puzzle::load(&root.join("2021/1"), 2021, 1).unwrap();
+        // Already there.
+        let second = fetch(&root, &client, 2021, 1, "someone").unwrap();
+        std::fs::remove_file(root.join("2021/1/io/someone.input")).unwrap();
+        // From the cache.
+        let third = fetch(&root, &client, 2021, 1, "someone").unwrap();
+        let missing = client.input(2021, 2, "someone");
+        let requests = server.requests();
+
+        assert!(no_session.is_err());
+        assert_eq!(
+            vec![
+                PathBuf::from("2021/1/io/someone.input"),
+                PathBuf::from("2021/1/README.md")
+            ],
+            first
+        );
+        assert_eq!("1\n2\n", input);
+        assert_eq!("Sonar Sweep", readme.unwrap().title);
+        assert!(second.is_empty());
+        assert_eq!(vec![PathBuf::from("2021/1/io/someone.input")], third);
+        assert!(missing.is_err());
+        let inputs = requests
+            .iter()
+            .filter(|request| *request == "GET /2021/day/1/input")
+            .count();
+        assert_eq!(1, inputs);
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::lang::Language;
+
+/// One solution registered in a day's `test.sh`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolutionConfig {
+    pub language: Language,
+    /// Solution path, as passed to the `lang/*.sh` script.
+    pub path: PathBuf,
+}
+impl SolutionConfig {
+    pub fn author(&self) -> String {
+        self.language.author(&self.path)
+    }
+}
+
+/// A single `{year}/{day}` directory.
+#[derive(Debug, Clone)]
+pub struct DayConfig {
+    pub year: u32,
+    pub day: u32,
+    pub dir: PathBuf,
+    pub solutions: Vec<SolutionConfig>,
+}
+impl DayConfig {
+    pub fn load(root: &Path, year: u32, day: u32) -> Result<Self> {
+        let dir = root.join(year.to_string()).join(day.to_string());
+        let test_sh = dir.join("test.sh");
+        let script = std::fs::read_to_string(&test_sh)
+            .with_context(|| format!("Failed to read {}.", test_sh.display()))?;
+        let solutions = parse_test_sh(&dir, &script)
+            .with_context(|| format!("Failed to parse {}.", test_sh.display()))?;
+        Ok(Self {
+            year,
+            day,
+            dir,
+            solutions,
+        })
+    }
+
+    pub fn io_dir(&self) -> PathBuf {
+        self.dir.join("io")
+    }
+}
+
+/// All `{year}` directories, in order.
+pub fn years(root: &Path) -> Result<Vec<u32>> {
+    let mut years = Vec::new();
+    for entry in
+        std::fs::read_dir(root).with_context(|| format!("Failed to read {}.", root.display()))?
+    {
+        let entry = entry?;
+        if let Some(year) = entry.file_name().to_str().and_then(|s| s.parse().ok()) {
+            if entry.path().is_dir() {
+                years.push(year);
+            }
+        }
+    }
+    years.sort_unstable();
+    Ok(years)
+}
+
+/// All days of `year` which have a `test.sh`, in order.
+pub fn days(root: &Path, year: u32) -> Result<Vec<u32>> {
+    let year_dir = root.join(year.to_string());
+    let mut days = Vec::new();
+    for entry in std::fs::read_dir(&year_dir)
+        .with_context(|| format!("Failed to read {}.", year_dir.display()))?
+    {
+        let entry = entry?;
+        let Some(day) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+            continue;
+        };
+        if entry.path().join("test.sh").is_file() {
+            days.push(day);
+        }
+    }
+    days.sort_unstable();
+    Ok(days)
+}
+
+/// Parses the `$D/../../lang/{lang}.sh "$D/solutions/..." "$D/io/*"` lines of a `test.sh`.
+/// Commented-out lines are skipped, just like bash would.
+pub fn parse_test_sh(day_dir: &Path, script: &str) -> Result<Vec<SolutionConfig>> {
+    let mut solutions = Vec::new();
+    for line in script.lines() {
+        let line = line.trim();
+        let Some(rest) = line.strip_prefix("$D/../../lang/") else {
+            continue;
+        };
+        let (script_name, args) = rest
+            .split_once(".sh")
+            .ok_or_else(|| anyhow!("Missing `.sh` in line: {}", line))?;
+        let language = Language::from_script(script_name)
+            .ok_or_else(|| anyhow!("Unknown language script `{}.sh`.", script_name))?;
+        let solution = args
+            .split('"')
+            .nth(1)
+            .and_then(|arg| arg.strip_prefix("$D/"))
+            .ok_or_else(|| anyhow!("Missing solution path in line: {}", line))?;
+        solutions.push(SolutionConfig {
+            language,
+            path: day_dir.join(solution.trim_end_matches('/')),
+        });
+    }
+    Ok(solutions)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_test_sh() {
+        let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+
+D=$(dirname $(realpath $0))
+
+echo "-- Day 2 --"
+$D/../../lang/cmake_cpp.sh	"$D/solutions/cmake_cpp/querijn/"		"$D/io/*"
+#$D/../../lang/python3.sh		"$D/solutions/python/slate/d5.py"		"$D/io/*"
+$D/../../lang/cargo_rust.sh             "$D/solutions/rust/mingweisamuel"               "$D/io/*"
+"#;
+        let solutions = parse_test_sh(Path::new("2021/2"), script).unwrap();
+        assert_eq!(
+            vec![
+                SolutionConfig {
+                    language: Language::CmakeCpp,
+                    path: PathBuf::from("2021/2/solutions/cmake_cpp/querijn"),
+                },
+                SolutionConfig {
+                    language: Language::CargoRust,
+                    path: PathBuf::from("2021/2/solutions/rust/mingweisamuel"),
+                },
+            ],
+            solutions
+        );
+        assert_eq!("querijn", solutions[0].author());
+    }
+}
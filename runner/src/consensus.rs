@@ -0,0 +1,296 @@
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::config::{DayConfig, SolutionConfig};
+use crate::exec::{self, IoFiles};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ConsensusOptions {
+    /// Distinct authors that must agree.
+    pub min_authors: usize,
+    /// Distinct languages that must agree.
+    pub min_languages: usize,
+}
+
+/// One solution's answer for an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub language: String,
+    pub author: String,
+    /// `None` if it crashed or timed out.
+    pub lines: Option<Vec<String>>,
+}
+impl Answer {
+    fn label(&self) -> String {
+        format!("{} {}", self.language, self.author)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    /// Every answer agrees, by enough authors and languages.
+    Agreed(Vec<String>),
+    /// Everyone agrees, but there are too few of them.
+    TooFew { authors: usize, languages: usize },
+    /// Per part (1-based), the differing answers and who gave them.
+    Disagreed(BTreeMap<usize, BTreeMap<String, Vec<String>>>),
+}
+
+/// Whether the answers agree. Solutions that crashed or timed out don't get a vote, see
+/// [`failures`].
+pub fn decide(answers: &[Answer], opts: &ConsensusOptions) -> Decision {
+    let answers: Vec<_> = answers
+        .iter()
+        .filter(|answer| answer.lines.is_some())
+        .collect();
+    let parts = answers
+        .iter()
+        .map(|answer| answer.lines.as_ref().map_or(0, Vec::len))
+        .max()
+        .unwrap_or(0);
+    let mut disagreements = BTreeMap::new();
+    for part in 0..parts {
+        let mut by_value = BTreeMap::<String, Vec<String>>::new();
+        for answer in &answers {
+            let value = answer
+                .lines
+                .as_ref()
+                .and_then(|lines| lines.get(part).cloned())
+                .unwrap_or_else(|| "(missing)".to_owned());
+            by_value.entry(value).or_default().push(answer.label());
+        }
+        if by_value.len() > 1 {
+            disagreements.insert(part + 1, by_value);
+        }
+    }
+    if !disagreements.is_empty() {
+        return Decision::Disagreed(disagreements);
+    }
+
+    let authors = answers
+        .iter()
+        .map(|answer| &answer.author)
+        .collect::<BTreeSet<_>>()
+        .len();
+    let languages = answers
+        .iter()
+        .map(|answer| &answer.language)
+        .collect::<BTreeSet<_>>()
+        .len();
+    match answers.first().and_then(|answer| answer.lines.clone()) {
+        Some(lines) if authors >= opts.min_authors && languages >= opts.min_languages => {
+            Decision::Agreed(lines)
+        }
+        _ => Decision::TooFew { authors, languages },
+    }
+}
+
+/// The solutions that crashed or timed out.
+pub fn failures(answers: &[Answer]) -> Vec<String> {
+    answers
+        .iter()
+        .filter(|answer| answer.lines.is_none())
+        .map(Answer::label)
+        .collect()
+}
+
+/// Builds every solution of the day and keeps the ones that pass all verified inputs, and at
+/// least one: an unverified solution doesn't get a say in the consensus.
+fn passing_solutions<'a>(day: &'a DayConfig, io: &IoFiles) -> Result<Vec<&'a SolutionConfig>> {
+    let mut passing = Vec::new();
+    'solutions: for solution in &day.solutions {
+        eprintln!(
+            "Checking {} {}...",
+            solution.language.display_name(),
+            solution.author()
+        );
+        solution.language.build(&solution.path)?;
+        let mut verified = 0;
+        for pair in &io.pairs {
+            let Some(output) = &pair.output else {
+                continue;
+            };
+            let run = exec::run_once(solution, &pair.input)?;
+            if run.timed_out || !exec::outputs_match(&std::fs::read(output)?, &run.stdout) {
+                eprintln!("  fails {}, skipping.", pair.input.display());
+                continue 'solutions;
+            }
+            verified += 1;
+        }
+        if verified == 0 {
+            eprintln!("  no verified inputs to pass, skipping.");
+            continue;
+        }
+        passing.push(solution);
+    }
+    Ok(passing)
+}
+
+/// Runs every passing solution on each unverified input and writes the `.output` files that
+/// reach consensus, unless `dry_run`. Returns whether no input had disagreeing answers.
+pub fn run(day: &DayConfig, opts: &ConsensusOptions, dry_run: bool) -> Result<bool> {
+    let io = exec::io_files(&day.io_dir())?;
+    let inputs: Vec<&Path> = io
+        .pairs
+        .iter()
+        .filter(|pair| pair.output.is_none())
+        .map(|pair| &*pair.input)
+        .collect();
+    if inputs.is_empty() {
+        println!("{}/{}: every input has an output.", day.year, day.day);
+        return Ok(true);
+    }
+
+    let solutions = passing_solutions(day, &io)?;
+    let mut success = true;
+    for input in inputs {
+        let mut answers = Vec::new();
+        for solution in &solutions {
+            let run = exec::run_once(solution, input)?;
+            let ok = !run.timed_out && run.status.is_some_and(|status| status.success());
+            answers.push(Answer {
+                language: solution.language.display_name().to_owned(),
+                author: solution.author(),
+                lines: ok.then(|| {
+                    String::from_utf8_lossy(&run.stdout)
+                        .lines()
+                        .map(|line| line.trim_end_matches('\r').to_owned())
+                        .collect()
+                }),
+            });
+        }
+
+        let failed = failures(&answers);
+        if !failed.is_empty() {
+            println!(
+                "{}: crashed or timed out, not counted: {}.",
+                input.display(),
+                failed.join(", ")
+            );
+        }
+        match decide(&answers, opts) {
+            Decision::Agreed(lines) => {
+                let output = input.with_extension("output");
+                println!(
+                    "{}: {} solutions agree, {} {}.",
+                    input.display(),
+                    answers.len() - failed.len(),
+                    if dry_run { "would write" } else { "writing" },
+                    output.display()
+                );
+                if !dry_run {
+                    let mut contents = lines.join("\n");
+                    contents.push('\n');
+                    std::fs::write(output, contents)?;
+                }
+            }
+            Decision::TooFew { authors, languages } => {
+                println!(
+                    "{}: no disagreement, but only {} author(s) in {} language(s) (need {} in {}).",
+                    input.display(),
+                    authors,
+                    languages,
+                    opts.min_authors,
+                    opts.min_languages
+                );
+            }
+            Decision::Disagreed(parts) => {
+                success = false;
+                println!("{}: solutions disagree.", input.display());
+                for (part, by_value) in parts {
+                    println!("  part {}:", part);
+                    for (value, labels) in by_value {
+                        println!("    {:<20} {}", value, labels.join(", "));
+                    }
+                }
+            }
+        }
+    }
+    Ok(success)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn answer(language: &str, author: &str, lines: Option<&[&str]>) -> Answer {
+        Answer {
+            language: language.to_owned(),
+            author: author.to_owned(),
+            lines: lines.map(|lines| lines.iter().map(|&line| line.to_owned()).collect()),
+        }
+    }
+
+    const OPTS: ConsensusOptions = ConsensusOptions {
+        min_authors: 2,
+        min_languages: 2,
+    };
+
+    #[test]
+    fn test_agreed() {
+        let answers = [
+            answer("Rust", "mingweisamuel", Some(&["1", "2"])),
+            answer("NodeJS", "stelar7", Some(&["1", "2"])),
+        ];
+        assert_eq!(
+            Decision::Agreed(vec!["1".to_owned(), "2".to_owned()]),
+            decide(&answers, &OPTS)
+        );
+    }
+
+    #[test]
+    fn test_too_few() {
+        let answers = [
+            answer("Rust", "mingweisamuel", Some(&["1", "2"])),
+            answer("Rust", "molenzwiebel", Some(&["1", "2"])),
+        ];
+        assert_eq!(
+            Decision::TooFew {
+                authors: 2,
+                languages: 1
+            },
+            decide(&answers, &OPTS)
+        );
+    }
+
+    #[test]
+    fn test_disagreed() {
+        let answers = [
+            answer("Rust", "mingweisamuel", Some(&["1", "2"])),
+            answer("NodeJS", "stelar7", Some(&["1", "3"])),
+            answer("Python3", "slate", None),
+        ];
+        let Decision::Disagreed(parts) = decide(&answers, &OPTS) else {
+            panic!();
+        };
+        // The crash is no answer, so part 1 agrees.
+        assert_eq!(vec![2], parts.keys().copied().collect::<Vec<_>>());
+        assert_eq!(vec!["NodeJS stelar7".to_owned()], parts[&2]["3"]);
+        assert_eq!(vec!["Python3 slate".to_owned()], failures(&answers));
+    }
+
+    #[test]
+    fn test_failures_excluded() {
+        let answers = [
+            answer("Rust", "mingweisamuel", Some(&["1", "2"])),
+            answer("Python3", "slate", None),
+            answer("NodeJS", "stelar7", Some(&["1", "2"])),
+        ];
+        assert_eq!(
+            Decision::Agreed(vec!["1".to_owned(), "2".to_owned()]),
+            decide(&answers, &OPTS)
+        );
+        let answers = [
+            answer("Rust", "mingweisamuel", Some(&["1", "2"])),
+            answer("NodeJS", "stelar7", None),
+        ];
+        assert_eq!(
+            Decision::TooFew {
+                authors: 1,
+                languages: 1
+            },
+            decide(&answers, &OPTS)
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use std::fmt::Write;
+
+use crate::leaderboard::{format_ms, Entry, Leaderboard, Status};
+
+/// An entry as placed on one side of the diff.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ranked {
+    /// 1-based position among passing entries, `None` if failed.
+    pub rank: Option<usize>,
+    pub entry: Entry,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryDiff {
+    pub before: Option<Ranked>,
+    pub after: Option<Ranked>,
+}
+impl EntryDiff {
+    fn entry(&self) -> &Entry {
+        self.after
+            .as_ref()
+            .or(self.before.as_ref())
+            .map(|ranked| &ranked.entry)
+            .unwrap()
+    }
+
+    pub fn status_changed(&self) -> bool {
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => before.entry.passed() != after.entry.passed(),
+            _ => false,
+        }
+    }
+
+    fn marker(&self) -> char {
+        match (&self.before, &self.after) {
+            (None, Some(_)) => '+',
+            (Some(_), None) => '-',
+            _ if self.status_changed() => '!',
+            _ => ' ',
+        }
+    }
+
+    fn rank_column(&self) -> String {
+        let fmt = |ranked: &Ranked| match ranked.rank {
+            Some(rank) => format!("#{}", rank),
+            None => "-".to_owned(),
+        };
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) if before.rank != after.rank => {
+                format!("{} -> {}", fmt(before), fmt(after))
+            }
+            (_, Some(ranked)) | (Some(ranked), None) => fmt(ranked),
+            (None, None) => unreachable!(),
+        }
+    }
+
+    fn time_column(&self) -> String {
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => {
+                let (b, a) = (before.entry.total_ms, after.entry.total_ms);
+                let delta = a as i64 - b as i64;
+                let pct = 100.0 * delta as f64 / b.max(1) as f64;
+                format!(
+                    "{} -> {} ({:+}ms, {:+.1}%)",
+                    format_ms(b),
+                    format_ms(a),
+                    delta,
+                    pct
+                )
+            }
+            (_, Some(ranked)) | (Some(ranked), None) => format_ms(ranked.entry.total_ms),
+            (None, None) => unreachable!(),
+        }
+    }
+
+    fn status_column(&self) -> String {
+        let fmt = |entry: &Entry| match entry.status {
+            Status::Passed => "✅",
+            Status::Failed { .. } => "❌",
+        };
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) if self.status_changed() => {
+                format!("{} -> {}", fmt(&before.entry), fmt(&after.entry))
+            }
+            _ => fmt(self.entry()).to_owned(),
+        }
+    }
+}
+
+fn ranked(leaderboard: Option<&Leaderboard>) -> Vec<Ranked> {
+    let mut rank = 0;
+    leaderboard
+        .map(|leaderboard| &*leaderboard.entries)
+        .unwrap_or_default()
+        .iter()
+        .map(|entry| Ranked {
+            rank: entry.passed().then(|| {
+                rank += 1;
+                rank
+            }),
+            entry: entry.clone(),
+        })
+        .collect()
+}
+
+/// Matches entries of two leaderboards of the same day by language and author. Entries are in
+/// `after` order, followed by removed entries.
+pub fn diff(before: Option<&Leaderboard>, after: Option<&Leaderboard>) -> Vec<EntryDiff> {
+    let mut before = ranked(before).into_iter().map(Some).collect::<Vec<_>>();
+    let mut diffs = Vec::new();
+    for after in ranked(after) {
+        let before = before
+            .iter_mut()
+            .find(|ranked| {
+                ranked
+                    .as_ref()
+                    .is_some_and(|ranked| ranked.entry.key() == after.entry.key())
+            })
+            .and_then(Option::take);
+        diffs.push(EntryDiff {
+            before,
+            after: Some(after),
+        });
+    }
+    diffs.extend(before.into_iter().flatten().map(|before| EntryDiff {
+        before: Some(before),
+        after: None,
+    }));
+    diffs
+}
+
+pub fn to_text(year: u32, day: u32, diffs: &[EntryDiff]) -> String {
+    let mut out = String::new();
+    writeln!(out, "-- {} Day {} --", year, day).unwrap();
+    writeln!(
+        out,
+        "  {:<10} {:<15} {:<10} {:<35} status",
+        "language", "author", "rank", "total"
+    )
+    .unwrap();
+    for diff in diffs {
+        let entry = diff.entry();
+        writeln!(
+            out,
+            "{} {:<10} {:<15} {:<10} {:<35} {}",
+            diff.marker(),
+            entry.language,
+            entry.author,
+            diff.rank_column(),
+            diff.time_column(),
+            diff.status_column(),
+        )
+        .unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BEFORE: &str = "
+-- Day 3 --
+Rust       mingweisamuel   4040ms     67ms       6ms        ✅
+Python3    slate           0ms        215ms      36ms       ✅
+NodeJS     stelar7         0ms        451ms      80ms       ✅
+golfscript pseudonym117    0ms        663ms      119ms      ✅
+";
+    const AFTER: &str = "
+-- Day 3 --
+Rust       mingweisamuel   4040ms     60ms       6ms        ✅
+NodeJS     stelar7         0ms        200ms      80ms       ✅
+Python3    slate           0ms        215ms      36ms       ✅
+golfscript pseudonym117    0ms        20012ms               ❌ /test/2022/3/io/stelar7.input
+C#         renkon          3731ms     717ms      81ms       ✅
+";
+
+    #[test]
+    fn test_diff() {
+        let before = Leaderboard::parse_text(2022, BEFORE).unwrap();
+        let after = Leaderboard::parse_text(2022, AFTER).unwrap();
+        let diffs = diff(Some(&before), Some(&after));
+        let lines: Vec<_> = diffs
+            .iter()
+            .map(|diff| (diff.marker(), diff.rank_column(), diff.status_column()))
+            .collect();
+        assert_eq!(
+            vec![
+                (' ', "#1".to_owned(), "✅".to_owned()),
+                (' ', "#3 -> #2".to_owned(), "✅".to_owned()),
+                (' ', "#2 -> #3".to_owned(), "✅".to_owned()),
+                ('!', "#4 -> -".to_owned(), "✅ -> ❌".to_owned()),
+                ('+', "#4".to_owned(), "✅".to_owned()),
+            ],
+            lines
+        );
+        assert_eq!("67ms -> 60ms (-7ms, -10.4%)", diffs[0].time_column());
+    }
+
+    #[test]
+    fn test_diff_removed() {
+        let before = Leaderboard::parse_text(2022, BEFORE).unwrap();
+        let diffs = diff(Some(&before), None);
+        assert_eq!(4, diffs.len());
+        assert!(diffs.iter().all(|diff| diff.marker() == '-'));
+    }
+}
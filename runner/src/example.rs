@@ -0,0 +1,238 @@
+use anyhow::{anyhow, Context, Result};
+
+use crate::config::DayConfig;
+use crate::exec;
+use crate::puzzle::{CodeBlock, Puzzle};
+
+/// How likely a code block is the example input, judging by the prose before it.
+fn score(block: &CodeBlock) -> u32 {
+    let context = block.context.to_lowercase();
+    let sentence = context.rsplit(". ").next().unwrap_or_default();
+    if context.contains("(your puzzle input)") && context.contains("example") {
+        3
+    } else if sentence.starts_with("for example") {
+        2
+    } else if context.contains("example") {
+        1
+    } else {
+        0
+    }
+}
+
+/// Whether a block only spells out bits, like 2021/16's packets: lines of binary digits, lines
+/// of capital letters labeling the bits above them, or a table like `A = 1010`. Plain binary
+/// lines alone are an example input (2021/3).
+fn is_bit_diagram(block: &CodeBlock) -> bool {
+    let is_binary = |line: &str| !line.is_empty() && line.bytes().all(|b| b == b'0' || b == b'1');
+    let is_labels = |line: &str| line.bytes().all(|b| b.is_ascii_uppercase());
+    let is_table_row = |line: &str| {
+        line.split_once(" = ")
+            .is_some_and(|(digit, bits)| digit.len() == 1 && is_binary(bits))
+    };
+    let lines: Vec<_> = block
+        .text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    lines.iter().any(|line| !is_binary(line))
+        && lines
+            .iter()
+            .all(|line| is_binary(line) || is_labels(line) || is_table_row(line))
+}
+
+/// The part one code block most likely to be the example input, with its index. The README
+/// usually introduces it with "For example" (and often "(your puzzle input)"), while diagrams
+/// and intermediate states aren't. Ties go to the longer block, as the full example comes after
+/// the excerpts explaining it (2021/19). Bit diagrams never are, though "For example" introduces
+/// them too (2021/16, whose examples are inline).
+pub fn candidate(puzzle: &Puzzle) -> Option<(usize, &CodeBlock)> {
+    let blocks = &puzzle.parts.first()?.code_blocks;
+    // `max_by_key` keeps the last of equal keys, so go in reverse to keep the first.
+    blocks
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, block)| !is_bit_diagram(block))
+        .max_by_key(|(_, block)| (score(block), block.text.len()))
+}
+
+/// Same contents, ignoring trailing whitespace (which the READMEs lose).
+fn same_lines(a: &str, b: &str) -> bool {
+    let lines = |s: &str| -> Vec<String> {
+        s.trim_end_matches('\n')
+            .lines()
+            .map(|line| line.trim_end().to_owned())
+            .collect()
+    };
+    lines(a) == lines(b)
+}
+
+/// Extracts the example input of a day from its README and compares it with (or, with `write`,
+/// creates) `io/example.input`. `block` picks a code block of part one instead of guessing.
+/// Returns whether the example input is consistent with the README.
+pub fn extract(day: &DayConfig, block: Option<usize>, write: bool) -> Result<bool> {
+    let name = format!("{}/{}", day.year, day.day);
+    let Some(puzzle) = Puzzle::load(&day.dir, day.year, day.day)? else {
+        println!("{}: no README.md.", name);
+        return Ok(true);
+    };
+    let chosen = match block {
+        Some(index) => puzzle
+            .parts
+            .first()
+            .and_then(|part| part.code_blocks.get(index))
+            .map(|code| (index, code))
+            .ok_or_else(|| anyhow!("{}: part one has no code block {}.", name, index))?,
+        None => match candidate(&puzzle) {
+            Some(chosen) => chosen,
+            None if puzzle
+                .parts
+                .first()
+                .is_some_and(|p| !p.code_blocks.is_empty()) =>
+            {
+                println!(
+                    "{}: README.md has no example-like code block in part one, pick one with \
+                     --block.",
+                    name
+                );
+                return Ok(true);
+            }
+            None => {
+                println!("{}: README.md has no code blocks in part one.", name);
+                return Ok(true);
+            }
+        },
+    };
+    let (index, code) = chosen;
+    let path = day.io_dir().join("example.input");
+    match std::fs::read_to_string(&path) {
+        Ok(existing) if same_lines(&existing, &code.text) => {
+            println!(
+                "{}: example.input matches README.md block {} (line {}).",
+                name, index, code.line
+            );
+            Ok(true)
+        }
+        Ok(_) => {
+            println!(
+                "{}: example.input differs from README.md block {} (line {}), keeping it.",
+                name, index, code.line
+            );
+            Ok(false)
+        }
+        Err(_) if write => {
+            std::fs::create_dir_all(day.io_dir())?;
+            std::fs::write(&path, &code.text)
+                .with_context(|| format!("Failed to write {}.", path.display()))?;
+            println!(
+                "{}: wrote example.input from README.md block {} (line {}).",
+                name, index, code.line
+            );
+            Ok(true)
+        }
+        Err(_) => {
+            println!(
+                "{}: no example.input, README.md block {} (line {}) is a candidate:\n{}",
+                name, index, code.line, code.text
+            );
+            Ok(true)
+        }
+    }
+}
+
+/// Runs every solution of the day on just `io/example.input`, as a smoke test before the full
+/// inputs. Returns whether all of them match `io/example.output`.
+pub fn smoke_test(day: &DayConfig) -> Result<bool> {
+    let name = format!("{}/{}", day.year, day.day);
+    let input = day.io_dir().join("example.input");
+    let output = day.io_dir().join("example.output");
+    if !input.is_file() {
+        return Ok(true);
+    }
+    let Ok(expected) = std::fs::read(&output) else {
+        println!(
+            "{}: no example.output to check against, `consensus` can write one.",
+            name
+        );
+        return Ok(true);
+    };
+    let mut success = true;
+    for solution in &day.solutions {
+        solution.language.build(&solution.path)?;
+        let run = exec::run_once(solution, &input)?;
+        let passed = !run.timed_out && exec::outputs_match(&expected, &run.stdout);
+        success &= passed;
+        println!(
+            "{:<10} {:<15} {}",
+            solution.language.display_name(),
+            solution.author(),
+            if passed { "✅" } else { "❌" }
+        );
+    }
+    Ok(success)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_candidate() {
+        let markdown = "## --- Day 10: Cathode-Ray Tube ---
+Consider the following small program:
+```
+noop
+```
+For example, consider this larger program:
+```
+addx 15
+```
+In the example above, ...
+```
+##..
+```
+";
+        let puzzle = Puzzle::parse(2022, 10, markdown).unwrap();
+        let (index, block) = candidate(&puzzle).unwrap();
+        assert_eq!(1, index);
+        assert_eq!("addx 15\n", block.text);
+
+        // 2021/16 only has bit diagrams, each after "For example", and a hex to binary table.
+        let markdown = "## --- Day 16: Packet Decoder ---
+Each character of hexadecimal corresponds to four bits of binary data:
+```
+0 = 0000
+F = 1111
+```
+For example, the hexadecimal string `D2FE28` becomes:
+```
+110100101111111000101000
+VVVTTTAAAAABBBBBCCCCC
+```
+";
+        assert!(candidate(&Puzzle::parse(2021, 16, markdown).unwrap()).is_none());
+        let day_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../2021/16");
+        let puzzle = Puzzle::load(&day_dir, 2021, 16).unwrap().unwrap();
+        assert_eq!(4, puzzle.parts[0].code_blocks.len());
+        assert!(candidate(&puzzle).is_none());
+        // 2021/3's example is plain binary.
+        let markdown = "## --- Day 3: Binary Diagnostic ---
+For example, given the following diagnostic report:
+```
+00100
+11110
+```
+";
+        assert_eq!(
+            0,
+            candidate(&Puzzle::parse(2021, 3, markdown).unwrap())
+                .unwrap()
+                .0
+        );
+
+        assert!(same_lines("    [D]    \n 1 \n", "    [D]\n 1\n\n"));
+        assert!(!same_lines("1\n2\n", "1\n"));
+    }
+}
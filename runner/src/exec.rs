@@ -0,0 +1,395 @@
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+use wait_timeout::ChildExt;
+
+use crate::config::{DayConfig, SolutionConfig};
+use crate::leaderboard::{Entry, Leaderboard, Status};
+use crate::puzzle::Puzzle;
+use crate::toolchain::Toolchains;
+
+/// Same limit as the `timeout 20s` in `lang/*.sh`.
+pub const RUN_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Consecutive output lines closer than this came from a single write, i.e. were buffered
+/// together. The reader gets those from one read within microseconds, while separate writes
+/// need it to wake up in between.
+const SAME_WRITE: Duration = Duration::from_micros(100);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IoPair {
+    pub input: PathBuf,
+    /// Expected output, `None` if nobody has verified the answers for this input yet.
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IoFiles {
+    pub pairs: Vec<IoPair>,
+    /// `.output` files without a matching `.input`.
+    pub orphan_outputs: Vec<PathBuf>,
+}
+
+/// Pairs up `{name}.input` with `{name}.output` in the io directory by name, independent of
+/// directory order. Other files are ignored.
+pub fn io_files(io_dir: &Path) -> Result<IoFiles> {
+    let mut io = IoFiles::default();
+    for entry in std::fs::read_dir(io_dir)
+        .with_context(|| format!("Failed to read {}.", io_dir.display()))?
+    {
+        let path = entry?.path();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("input") => {
+                let output = path.with_extension("output");
+                io.pairs.push(IoPair {
+                    input: path,
+                    output: output.is_file().then_some(output),
+                });
+            }
+            Some("output") if !path.with_extension("input").is_file() => {
+                io.orphan_outputs.push(path);
+            }
+            _ => {}
+        }
+    }
+    io.pairs.sort_by(|a, b| a.input.cmp(&b.input));
+    io.orphan_outputs.sort();
+    Ok(io)
+}
+
+/// Result of running one solution on one input.
+#[derive(Debug)]
+pub struct RunOutput {
+    pub elapsed: Duration,
+    pub stdout: Vec<u8>,
+    /// Arrival time of each stdout line, relative to spawning the process.
+    pub line_times: Vec<Duration>,
+    /// `None` if it failed to spawn or was killed after timing out.
+    pub status: Option<ExitStatus>,
+    pub timed_out: bool,
+}
+impl RunOutput {
+    /// Whether every line came within `SAME_WRITE` of the one before, as when a block-buffered
+    /// solution (C++ with `'\n'`, Java, Go...) prints everything at once, however long it takes
+    /// to exit after that. `None` with fewer than two lines.
+    pub fn buffered(&self) -> Option<bool> {
+        (self.line_times.len() > 1).then(|| {
+            self.line_times
+                .windows(2)
+                .all(|pair| pair[1].saturating_sub(pair[0]) < SAME_WRITE)
+        })
+    }
+
+    /// Time spent on each part (one line each): part 1 from spawn until its line arrived, later
+    /// parts from the previous line. Meaningless if the output was `buffered`: part 1 would get
+    /// the whole runtime and later parts would look free.
+    pub fn part_times(&self) -> Vec<Duration> {
+        let mut prev = Duration::ZERO;
+        self.line_times
+            .iter()
+            .map(|&time| {
+                let part = time.saturating_sub(prev);
+                prev = time;
+                part
+            })
+            .collect()
+    }
+}
+
+pub fn run_once(solution: &SolutionConfig, input: &Path) -> Result<RunOutput> {
+    let mut stdin =
+        std::fs::read(input).with_context(|| format!("Failed to read {}.", input.display()))?;
+    if solution.language.strips_cr() {
+        stdin.retain(|&b| b != b'\r');
+    }
+
+    let mut cmd = solution.language.command(&solution.path, input)?;
+    cmd.stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+
+    let start = Instant::now();
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        // Missing binary, e.g. after a failed compile.
+        Err(_) => {
+            return Ok(RunOutput {
+                elapsed: start.elapsed(),
+                stdout: Vec::new(),
+                line_times: Vec::new(),
+                status: None,
+                timed_out: false,
+            })
+        }
+    };
+
+    let mut child_stdin = child.stdin.take().unwrap();
+    let writer = std::thread::spawn(move || {
+        // Solutions may exit without reading all of stdin.
+        let _ = child_stdin.write_all(&stdin);
+    });
+    let mut child_stdout = BufReader::new(child.stdout.take().unwrap());
+    let reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let mut line_times = Vec::new();
+        while let Ok(1..) = child_stdout.read_until(b'\n', &mut buf) {
+            if buf.ends_with(b"\n") {
+                line_times.push(start.elapsed());
+            }
+        }
+        (buf, line_times)
+    });
+
+    let status = match child.wait_timeout(RUN_TIMEOUT) {
+        Ok(status) => status,
+        Err(err) => {
+            // Like a timeout, so neither the child nor the threads outlive the run.
+            let _ = child.kill();
+            let _ = child.wait();
+            let _ = writer.join();
+            let _ = reader.join();
+            return Err(err).context("Failed to wait for the solution.");
+        }
+    };
+    let timed_out = status.is_none();
+    if timed_out {
+        child.kill()?;
+        child.wait()?;
+    }
+    let elapsed = start.elapsed();
+    let _ = writer.join();
+    let (stdout, line_times) = reader.join().unwrap_or_default();
+    Ok(RunOutput {
+        elapsed,
+        stdout,
+        line_times,
+        status,
+        timed_out,
+    })
+}
+
+/// Same as `diff --strip-trailing-cr`.
+pub fn outputs_match(expected: &[u8], actual: &[u8]) -> bool {
+    fn lines(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+        bytes
+            .split_inclusive(|&b| b == b'\n')
+            .map(|line| line.strip_suffix(b"\n").unwrap_or(line))
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+    }
+    let ends_with_newline = |bytes: &[u8]| bytes.last().is_none_or(|&b| b == b'\n');
+    ends_with_newline(expected) == ends_with_newline(actual) && lines(expected).eq(lines(actual))
+}
+
+/// Builds and runs one solution on every io pair, `runs` times, stopping at the first failure.
+/// Inputs without an expected output only fail on crashes and timeouts, and are listed as
+/// unverified.
+pub fn run_solution(
+    solution: &SolutionConfig,
+    pairs: &[IoPair],
+    runs: usize,
+    toolchains: &Toolchains,
+) -> Result<Entry> {
+    let compile = solution.language.build(&solution.path)?;
+    let mut entry = Entry {
+        language: solution.language.display_name().to_owned(),
+        author: solution.author(),
+        compile_ms: compile.as_millis() as u64,
+        total_ms: 0,
+        average_ms: 0,
+        status: Status::Passed,
+        samples_ms: Vec::new(),
+        parts_ms: Vec::new(),
+        unverified: Vec::new(),
+        toolchain: toolchains.get(solution.language.script()).cloned(),
+        solved_s: Vec::new(),
+    };
+
+    // Per-part times summed over inputs and runs. Only dropped if no output showed a gap between
+    // lines: a solution that flushes each line may still have a part quicker than `SAME_WRITE`,
+    // or the reader may wake up late, but not on every input.
+    let mut parts = Vec::<Duration>::new();
+    let (mut buffered, mut streamed) = (false, false);
+    for _ in 0..runs.max(1) {
+        let mut total = Duration::ZERO;
+        for pair in pairs {
+            let output = run_once(solution, &pair.input)?;
+            let correct = match &pair.output {
+                Some(path) => {
+                    let expected = std::fs::read(path)
+                        .with_context(|| format!("Failed to read {}.", path.display()))?;
+                    outputs_match(&expected, &output.stdout)
+                }
+                None => output.status.is_some_and(|status| status.success()),
+            };
+            if output.timed_out || !correct {
+                entry.total_ms = output.elapsed.as_millis() as u64;
+                entry.status = Status::Failed {
+                    input: pair.input.display().to_string(),
+                };
+                entry.samples_ms.clear();
+                return Ok(entry);
+            }
+            total += output.elapsed;
+            match output.buffered() {
+                Some(true) => buffered = true,
+                Some(false) => streamed = true,
+                None => {}
+            }
+            for (i, part) in output.part_times().into_iter().enumerate() {
+                if parts.len() <= i {
+                    parts.push(Duration::ZERO);
+                }
+                parts[i] += part;
+            }
+        }
+        entry.samples_ms.push(total.as_millis() as u64);
+    }
+
+    entry.unverified = pairs
+        .iter()
+        .filter(|pair| pair.output.is_none())
+        .map(|pair| pair.input.display().to_string())
+        .collect();
+    let runs = entry.samples_ms.len() as u64;
+    entry.total_ms = entry.samples_ms.iter().sum::<u64>() / runs;
+    entry.average_ms = entry.total_ms / (pairs.len() as u64).max(1);
+    if streamed || !buffered {
+        entry.parts_ms = parts
+            .into_iter()
+            .map(|part| part.as_millis() as u64 / runs)
+            .collect();
+    }
+    Ok(entry)
+}
+
+/// Runs every solution of a day, like `make test`. `toolchains` are probed once per invocation
+/// and copied into the leaderboard for the languages it uses.
+pub fn run_day(day: &DayConfig, runs: usize, toolchains: &Toolchains) -> Result<Leaderboard> {
+    let io = io_files(&day.io_dir())?;
+    let errors = io
+        .orphan_outputs
+        .iter()
+        .map(|path| format!("{} has no matching .input", path.display()))
+        .collect();
+    let mut entries = Vec::new();
+    for solution in &day.solutions {
+        eprintln!(
+            "Running {} {}/{} {} {}...",
+            solution.language.display_name(),
+            day.year,
+            day.day,
+            solution.author(),
+            solution.path.display(),
+        );
+        entries.push(run_solution(solution, &io.pairs, runs, toolchains)?);
+    }
+    let toolchains = day
+        .solutions
+        .iter()
+        .filter_map(|solution| {
+            let script = solution.language.script();
+            Some((script.to_owned(), toolchains.get(script)?.clone()))
+        })
+        .collect();
+    let title = match Puzzle::load(&day.dir, day.year, day.day) {
+        Ok(puzzle) => puzzle.map(|puzzle| puzzle.title),
+        Err(err) => {
+            eprintln!("{:#}", err);
+            None
+        }
+    };
+    let mut leaderboard = Leaderboard {
+        year: day.year,
+        day: day.day,
+        title,
+        toolchains,
+        errors,
+        entries,
+    };
+    leaderboard.sort();
+    Ok(leaderboard)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn test_outputs_match() {
+        assert!(outputs_match(b"1\n2\n", b"1\n2\n"));
+        assert!(outputs_match(b"1\r\n2\r\n", b"1\n2\n"));
+        assert!(!outputs_match(b"1\n2\n", b"1\n2"));
+        assert!(!outputs_match(b"1\n2\n", b"1\n3\n"));
+        assert!(!outputs_match(b"1\n2\n", b"1\n"));
+    }
+
+    #[test]
+    fn test_io_files() {
+        let dir = TempDir::new("io");
+        // `a.input` without output used to shift every later pair with `xargs -n2`.
+        for name in ["a.input", "b.input", "b.output", "c.output", "README.md"] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+        let io = io_files(&dir).unwrap();
+        assert_eq!(
+            IoFiles {
+                pairs: vec![
+                    IoPair {
+                        input: dir.join("a.input"),
+                        output: None,
+                    },
+                    IoPair {
+                        input: dir.join("b.input"),
+                        output: Some(dir.join("b.output")),
+                    },
+                ],
+                orphan_outputs: vec![dir.join("c.output")],
+            },
+            io
+        );
+    }
+
+    #[test]
+    fn test_part_times() {
+        let output = RunOutput {
+            elapsed: Duration::from_millis(40),
+            stdout: b"1\n2\n".to_vec(),
+            line_times: vec![Duration::from_millis(5), Duration::from_millis(35)],
+            status: None,
+            timed_out: false,
+        };
+        assert_eq!(
+            vec![Duration::from_millis(5), Duration::from_millis(30)],
+            output.part_times()
+        );
+        assert_eq!(Some(false), output.buffered());
+
+        // A fast part 2 still counts, however long the exit takes.
+        let fast = RunOutput {
+            elapsed: Duration::from_millis(200),
+            line_times: vec![Duration::from_millis(39), Duration::from_micros(39_300)],
+            ..output
+        };
+        assert_eq!(Some(false), fast.buffered());
+        assert_eq!(
+            vec![Duration::from_millis(39), Duration::from_micros(300)],
+            fast.part_times()
+        );
+        // Both lines flushed together, e.g. at exit.
+        let buffered = RunOutput {
+            line_times: vec![Duration::from_millis(39), Duration::from_micros(39_004)],
+            ..fast
+        };
+        assert_eq!(Some(true), buffered.buffered());
+        let one_line = RunOutput {
+            stdout: b"1\n".to_vec(),
+            line_times: vec![Duration::from_millis(39)],
+            ..buffered
+        };
+        assert_eq!(None, one_line.buffered());
+        assert_eq!(vec![Duration::from_millis(39)], one_line.part_times());
+    }
+}
@@ -0,0 +1,60 @@
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+fn git(root: &Path) -> Command {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(root);
+    cmd
+}
+
+/// Resolves `rev` to a full commit hash.
+pub fn rev_parse(root: &Path, rev: &str) -> Result<String> {
+    let output = git(root)
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("{}^{{commit}}", rev))
+        .output()
+        .context("Failed to run git.")?;
+    if !output.status.success() {
+        bail!("Unknown git revision `{}`.", rev);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Contents of `path` (relative to the repo root) at `rev`, or `None` if it does not exist there.
+pub fn show(root: &Path, rev: &str, path: &str) -> Result<Option<String>> {
+    let output = git(root)
+        .arg("show")
+        .arg(format!("{}:{}", rev, path))
+        .output()
+        .context("Failed to run git.")?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    String::from_utf8(output.stdout)
+        .with_context(|| format!("{} at {} is not UTF-8.", path, rev))
+        .map(Some)
+}
+
+/// Names of the entries directly inside `dir` at `rev`.
+pub fn ls_tree(root: &Path, rev: &str, dir: &str) -> Result<Vec<String>> {
+    let output = git(root)
+        .args(["ls-tree", "--name-only"])
+        .arg(rev)
+        .arg(format!("{}/", dir.trim_end_matches('/')))
+        .output()
+        .context("Failed to run git.")?;
+    if !output.status.success() {
+        bail!(
+            "git ls-tree {} {} failed: {}",
+            rev,
+            dir,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.rsplit('/').next())
+        .map(str::to_owned)
+        .collect())
+}
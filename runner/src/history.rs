@@ -0,0 +1,394 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::leaderboard::{Entry, Leaderboard, Status};
+use crate::submit::Verdict;
+use crate::toolchain::Toolchains;
+
+/// Machine the results were measured on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Host {
+    pub hostname: String,
+    pub os: String,
+    pub arch: String,
+    pub cpu: String,
+    pub cpus: usize,
+}
+impl Host {
+    pub fn current() -> Self {
+        let hostname = std::fs::read_to_string("/etc/hostname")
+            .map(|name| name.trim().to_owned())
+            .unwrap_or_default();
+        let cpu = std::fs::read_to_string("/proc/cpuinfo")
+            .ok()
+            .and_then(|info| {
+                info.lines()
+                    .find(|line| line.starts_with("model name"))
+                    .and_then(|line| line.split_once(':'))
+                    .map(|(_, model)| model.trim().to_owned())
+            })
+            .unwrap_or_default();
+        Self {
+            hostname,
+            os: std::env::consts::OS.to_owned(),
+            arch: std::env::consts::ARCH.to_owned(),
+            cpu,
+            cpus: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+/// Current Unix seconds.
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Metadata of one invocation of the runner.
+#[derive(Debug, Clone)]
+pub struct RunInfo {
+    pub commit: Option<String>,
+    /// Unix seconds.
+    pub timestamp: i64,
+    pub toolchains: Toolchains,
+    pub host: Host,
+}
+impl RunInfo {
+    pub fn new(commit: Option<String>, toolchains: Toolchains) -> Self {
+        Self {
+            commit,
+            timestamp: now(),
+            toolchains,
+            host: Host::current(),
+        }
+    }
+}
+
+/// One result of a solution, as recorded in the database.
+#[derive(Debug, Clone)]
+pub struct HistoryRow {
+    pub commit: Option<String>,
+    /// `YYYY-MM-DD HH:MM:SS`, UTC.
+    pub datetime: String,
+    pub entry: Entry,
+}
+
+/// One answer posted to adventofcode.com.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    /// Unix seconds.
+    pub timestamp: i64,
+    pub year: u32,
+    pub day: u32,
+    pub username: String,
+    pub part: u32,
+    pub answer: String,
+    pub verdict: Verdict,
+    /// Unix seconds until which the account can't submit again, if the response said so.
+    pub wait_until: Option<i64>,
+}
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    commit_hash TEXT,
+    timestamp INTEGER NOT NULL,
+    toolchains TEXT NOT NULL,
+    host TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS results (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    year INTEGER NOT NULL,
+    day INTEGER NOT NULL,
+    language TEXT NOT NULL,
+    author TEXT NOT NULL,
+    compile_ms INTEGER NOT NULL,
+    total_ms INTEGER NOT NULL,
+    average_ms INTEGER NOT NULL,
+    failed_input TEXT,
+    samples_ms TEXT NOT NULL,
+    parts_ms TEXT NOT NULL DEFAULT '[]'
+);
+CREATE INDEX IF NOT EXISTS results_solution ON results(year, day, author, language);
+CREATE TABLE IF NOT EXISTS submissions (
+    id INTEGER PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    year INTEGER NOT NULL,
+    day INTEGER NOT NULL,
+    username TEXT NOT NULL,
+    part INTEGER NOT NULL,
+    answer TEXT NOT NULL,
+    verdict TEXT NOT NULL,
+    wait_until INTEGER
+);
+CREATE INDEX IF NOT EXISTS submissions_day ON submissions(year, day, username);
+";
+
+/// Columns added after the first version of `SCHEMA`, for databases created before them.
+const MIGRATIONS: &[(&str, &str, &str)] = &[("results", "parts_ms", "TEXT NOT NULL DEFAULT '[]'")];
+
+fn migrate(conn: &Connection) -> Result<()> {
+    conn.execute_batch(SCHEMA)?;
+    for (table, column, decl) in MIGRATIONS {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)",
+            params![table, column],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            conn.execute_batch(&format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table, column, decl
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Local SQLite database of every run's results.
+pub struct History {
+    conn: Connection,
+}
+impl History {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open {}.", path.display()))?;
+        migrate(&conn)?;
+        Ok(Self { conn })
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        migrate(&conn)?;
+        Ok(Self { conn })
+    }
+
+    /// Records a run and all of its leaderboards, returning the run id.
+    pub fn record(&mut self, info: &RunInfo, leaderboards: &[Leaderboard]) -> Result<i64> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO runs (commit_hash, timestamp, toolchains, host) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                info.commit,
+                info.timestamp,
+                serde_json::to_string(&info.toolchains)?,
+                serde_json::to_string(&info.host)?,
+            ],
+        )?;
+        let run_id = tx.last_insert_rowid();
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO results (run_id, year, day, language, author, compile_ms, total_ms,
+                    average_ms, failed_input, samples_ms, parts_ms)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?;
+            for leaderboard in leaderboards {
+                for entry in &leaderboard.entries {
+                    let failed_input = match &entry.status {
+                        Status::Passed => None,
+                        Status::Failed { input } => Some(input),
+                    };
+                    insert.execute(params![
+                        run_id,
+                        leaderboard.year,
+                        leaderboard.day,
+                        entry.language,
+                        entry.author,
+                        entry.compile_ms as i64,
+                        entry.total_ms as i64,
+                        entry.average_ms as i64,
+                        failed_input,
+                        serde_json::to_string(&entry.samples_ms)?,
+                        serde_json::to_string(&entry.parts_ms)?,
+                    ])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(run_id)
+    }
+
+    /// All recorded results of one solution, oldest first. `language` may be omitted if the
+    /// author only has one solution that day.
+    pub fn query(
+        &self,
+        year: u32,
+        day: u32,
+        author: &str,
+        language: Option<&str>,
+    ) -> Result<Vec<HistoryRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT runs.commit_hash, datetime(runs.timestamp, 'unixepoch'), results.language,
+                results.compile_ms, results.total_ms, results.average_ms, results.failed_input,
+                results.samples_ms, results.parts_ms
+            FROM results JOIN runs ON runs.id = results.run_id
+            WHERE results.year = ?1 AND results.day = ?2 AND results.author = ?3
+                AND (?4 IS NULL OR results.language = ?4)
+            ORDER BY runs.timestamp, runs.id",
+        )?;
+        let rows = stmt.query_map(params![year, day, author, language], |row| {
+            let failed_input: Option<String> = row.get(6)?;
+            let samples_ms: String = row.get(7)?;
+            let parts_ms: String = row.get(8)?;
+            Ok((
+                HistoryRow {
+                    commit: row.get(0)?,
+                    datetime: row.get(1)?,
+                    entry: Entry {
+                        language: row.get(2)?,
+                        author: author.to_owned(),
+                        compile_ms: row.get::<_, i64>(3)? as u64,
+                        total_ms: row.get::<_, i64>(4)? as u64,
+                        average_ms: row.get::<_, i64>(5)? as u64,
+                        status: match failed_input {
+                            None => Status::Passed,
+                            Some(input) => Status::Failed { input },
+                        },
+                        samples_ms: Vec::new(),
+                        parts_ms: Vec::new(),
+                        unverified: Vec::new(),
+                        toolchain: None,
+                        solved_s: Vec::new(),
+                    },
+                },
+                samples_ms,
+                parts_ms,
+            ))
+        })?;
+        rows.map(|row| {
+            let (mut row, samples_ms, parts_ms) = row?;
+            row.entry.samples_ms = serde_json::from_str(&samples_ms)?;
+            row.entry.parts_ms = serde_json::from_str(&parts_ms)?;
+            Ok(row)
+        })
+        .collect()
+    }
+
+    pub fn record_submission(&self, submission: &Submission) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO submissions (timestamp, year, day, username, part, answer, verdict,
+                wait_until)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                submission.timestamp,
+                submission.year,
+                submission.day,
+                submission.username,
+                submission.part,
+                submission.answer,
+                submission.verdict.as_str(),
+                submission.wait_until,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Every answer `username` submitted for a day, oldest first.
+    pub fn submissions(&self, year: u32, day: u32, username: &str) -> Result<Vec<Submission>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT timestamp, part, answer, verdict, wait_until FROM submissions
+            WHERE year = ?1 AND day = ?2 AND username = ?3
+            ORDER BY timestamp, id",
+        )?;
+        let rows = stmt.query_map(params![year, day, username], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get::<_, String>(3)?,
+                row.get(4)?,
+            ))
+        })?;
+        rows.map(|row| {
+            let (timestamp, part, answer, verdict, wait_until) = row?;
+            Ok(Submission {
+                timestamp,
+                year,
+                day,
+                username: username.to_owned(),
+                part,
+                answer,
+                verdict: Verdict::parse(&verdict)
+                    .with_context(|| format!("Unknown verdict `{}`.", verdict))?,
+                wait_until,
+            })
+        })
+        .collect()
+    }
+
+    /// Until when `username` is rate limited on any day, in Unix seconds.
+    pub fn wait_until(&self, username: &str) -> Result<Option<i64>> {
+        Ok(self.conn.query_row(
+            "SELECT MAX(wait_until) FROM submissions WHERE username = ?1",
+            params![username],
+            |row| row.get(0),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_record_query() {
+        let mut history = History::open_in_memory().unwrap();
+        let leaderboard = Leaderboard::parse_text(
+            2022,
+            "
+-- Day 3 --
+Rust       mingweisamuel   4040ms     67ms       6ms        ✅ parts: 2ms 65ms
+NodeJS     mingweisamuel   0ms        20012ms               ❌ 2022/3/io/stelar7.input
+",
+        )
+        .unwrap();
+        let mut info = RunInfo::new(Some("abc".to_owned()), Toolchains::new());
+        history
+            .record(&info, std::slice::from_ref(&leaderboard))
+            .unwrap();
+        info.timestamp += 60;
+        history
+            .record(&info, std::slice::from_ref(&leaderboard))
+            .unwrap();
+
+        let rows = history.query(2022, 3, "mingweisamuel", None).unwrap();
+        assert_eq!(4, rows.len());
+        let rows = history
+            .query(2022, 3, "mingweisamuel", Some("NodeJS"))
+            .unwrap();
+        assert_eq!(2, rows.len());
+        assert_eq!(leaderboard.entries[1], rows[0].entry);
+        let rows = history
+            .query(2022, 3, "mingweisamuel", Some("Rust"))
+            .unwrap();
+        assert_eq!(vec![2, 65], rows[0].entry.parts_ms);
+        assert_eq!(Some("abc"), rows[1].commit.as_deref());
+        assert!(history
+            .query(2022, 4, "mingweisamuel", None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_migrate() {
+        // A database from before `parts_ms`.
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(&SCHEMA.replace(",\n    parts_ms TEXT NOT NULL DEFAULT '[]'", ""))
+            .unwrap();
+        conn.execute_batch(
+            "INSERT INTO runs VALUES (1, NULL, 0, '{}', '{}');
+            INSERT INTO results VALUES (1, 2022, 3, 'Rust', 'slate', 0, 5, 5, NULL, '[]');",
+        )
+        .unwrap();
+        migrate(&conn).unwrap();
+        migrate(&conn).unwrap();
+        let history = History { conn };
+        let rows = history.query(2022, 3, "slate", None).unwrap();
+        assert!(rows[0].entry.parts_ms.is_empty());
+    }
+}
@@ -0,0 +1,217 @@
+/// Converts the `<article class="day-desc">` parts of an Advent of Code puzzle page to the
+/// repository's README.md style: `## --- Day 1: Sonar Sweep ---` headings, fenced code blocks,
+/// `**emphasis**` and `` `code` ``. Returns `None` if the page has no puzzle description.
+pub fn puzzle_markdown(html: &str) -> Option<String> {
+    let mut markdown = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("<article class=\"day-desc\"") {
+        let article = &rest[start..];
+        let end = article.find("</article>").unwrap_or(article.len());
+        if !markdown.is_empty() {
+            markdown.push_str("\n\n");
+        }
+        markdown.push_str(&article_markdown(&article[..end]));
+        rest = &article[end..];
+    }
+    if markdown.is_empty() {
+        return None;
+    }
+    // Tidy: outside code blocks, no indentation from the HTML's line breaks and at most one
+    // blank line in a row.
+    let mut tidy = String::new();
+    let mut blank = false;
+    let mut in_code = false;
+    for line in markdown.lines() {
+        let line = if in_code { line } else { line.trim() };
+        if line == "```" {
+            in_code = !in_code;
+        }
+        if line.is_empty() && !in_code {
+            if !blank && !tidy.is_empty() {
+                tidy.push('\n');
+            }
+            blank = true;
+            continue;
+        }
+        blank = false;
+        tidy.push_str(line);
+        tidy.push('\n');
+    }
+    tidy.truncate(tidy.trim_end().len());
+    tidy.push('\n');
+    Some(tidy)
+}
+
+/// Text of the first `<article>`, without tags and with whitespace collapsed, e.g. the verdict
+/// of an answer submission.
+pub fn article_text(html: &str) -> Option<String> {
+    let start = html.find("<article")?;
+    let article = &html[start..];
+    let article = &article[..article.find("</article>").unwrap_or(article.len())];
+    let mut text = String::new();
+    let mut rest = article;
+    while let Some(lt) = rest.find('<') {
+        text.push_str(&rest[..lt]);
+        let Some(gt) = rest[lt..].find('>') else {
+            rest = "";
+            break;
+        };
+        rest = &rest[lt + gt + 1..];
+    }
+    text.push_str(rest);
+    let text = decode_entities(&text);
+    Some(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+fn article_markdown(html: &str) -> String {
+    let mut out = String::new();
+    let mut in_pre = false;
+    let mut in_code = false;
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            push_text(&mut out, rest, in_pre);
+            break;
+        };
+        push_text(&mut out, &rest[..lt], in_pre);
+        let Some(gt) = rest[lt..].find('>') else {
+            break;
+        };
+        let tag = &rest[lt + 1..lt + gt];
+        rest = &rest[lt + gt + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match (name.as_str(), closing) {
+            ("h2", false) => out.push_str("## "),
+            ("h2", true) => out.push('\n'),
+            ("p", true) => out.push_str("\n\n"),
+            ("pre", false) => {
+                out.push_str("\n```\n");
+                in_pre = true;
+            }
+            ("pre", true) => {
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                out.push_str("```\n\n");
+                in_pre = false;
+            }
+            ("code", _) if !in_pre => {
+                out.push('`');
+                in_code = !closing;
+            }
+            ("em", _) if !in_pre && !in_code => out.push_str("**"),
+            ("li", false) => out.push_str("- "),
+            ("li", true) | ("br", _) => out.push('\n'),
+            ("ul", true) => out.push('\n'),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Appends decoded text. Outside `<pre>`, newlines are just whitespace.
+fn push_text(out: &mut String, text: &str, in_pre: bool) {
+    let text = decode_entities(text);
+    if in_pre {
+        out.push_str(&text);
+    } else {
+        out.push_str(&text.replace('\n', " "));
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';').filter(|&semi| semi <= 10) else {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::puzzle::Puzzle;
+
+    #[test]
+    fn test_puzzle_markdown() {
+        let html = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 1: Sonar Sweep ---</h2><p>For example, suppose you had the following <em>report</em>:</p>
+<pre><code>199
+<em>200</em>
+</code></pre>
+<p>There are <code><em>7</em></code> measurements &gt; the previous one.</p>
+<ul>
+<li>one</li>
+<li>two</li>
+</ul>
+</article>
+<p>Your puzzle answer was <code>1527</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>Again&#39;s &amp; more.</p>
+</article>
+</main></body></html>"#;
+        let markdown = puzzle_markdown(html).unwrap();
+        assert_eq!(
+            "## --- Day 1: Sonar Sweep ---
+For example, suppose you had the following **report**:
+
+```
+199
+200
+```
+
+There are `7` measurements > the previous one.
+
+- one
+- two
+
+## --- Part Two ---
+Again's & more.
+",
+            markdown
+        );
+        let puzzle = Puzzle::parse(2021, 1, &markdown).unwrap();
+        assert_eq!("Sonar Sweep", puzzle.title);
+        assert_eq!(2, puzzle.parts.len());
+        assert_eq!("199\n200\n", puzzle.parts[0].code_blocks[0].text);
+        assert_eq!(None, puzzle_markdown("<html>Please log in.</html>"));
+    }
+}
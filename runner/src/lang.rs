@@ -0,0 +1,335 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use wait_timeout::ChildExt;
+
+/// The languages supported by `lang/*.sh`. Each variant mirrors the build and run steps of its
+/// script so results are comparable to the ones `make test` produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    CargoRust,
+    CmakeCpp,
+    DotnetCSharp,
+    Golang,
+    Golfscript,
+    Java,
+    MonoCSharp,
+    Node,
+    Osabie,
+    Perl,
+    Python3,
+}
+impl Language {
+    pub const ALL: [Self; 11] = [
+        Self::CargoRust,
+        Self::CmakeCpp,
+        Self::DotnetCSharp,
+        Self::Golang,
+        Self::Golfscript,
+        Self::Java,
+        Self::MonoCSharp,
+        Self::Node,
+        Self::Osabie,
+        Self::Perl,
+        Self::Python3,
+    ];
+
+    /// Name of the `lang/{script}.sh` script, without extension.
+    pub fn script(self) -> &'static str {
+        match self {
+            Self::CargoRust => "cargo_rust",
+            Self::CmakeCpp => "cmake_cpp",
+            Self::DotnetCSharp => "dotnet_c_sharp",
+            Self::Golang => "golang",
+            Self::Golfscript => "golfscript",
+            Self::Java => "java",
+            Self::MonoCSharp => "mono_c_sharp",
+            Self::Node => "node",
+            Self::Osabie => "osabie",
+            Self::Perl => "perl",
+            Self::Python3 => "python3",
+        }
+    }
+
+    pub fn from_script(script: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|lang| lang.script() == script)
+    }
+
+    /// Parses either the script name (`cargo_rust`) or the directory name (`rust`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::from_script(name)
+            .or_else(|| Self::ALL.into_iter().find(|lang| lang.dir_name() == name))
+    }
+
+    /// Name of the `{year}/{day}/solutions/{language}` directory.
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            Self::CargoRust => "rust",
+            Self::CmakeCpp => "cmake_cpp",
+            Self::DotnetCSharp => "dotnet_c_sharp",
+            Self::Golang => "golang",
+            Self::Golfscript => "golfscript",
+            Self::Java => "java",
+            Self::MonoCSharp => "mono_c_sharp",
+            Self::Node => "node",
+            Self::Osabie => "05ab1e",
+            Self::Perl => "perl",
+            Self::Python3 => "python",
+        }
+    }
+
+    /// Checks that the file the build or run step needs exists, returning its expected path
+    /// (or pattern) if not.
+    pub fn missing_entry_file(self, solution: &Path) -> Option<PathBuf> {
+        let has_extension = |ext: &str| {
+            std::fs::read_dir(solution).is_ok_and(|entries| {
+                entries
+                    .flatten()
+                    .any(|entry| entry.path().extension().is_some_and(|e| e == ext))
+            })
+        };
+        let (found, expected) = match self {
+            Self::CargoRust => {
+                let path = solution.join("Cargo.toml");
+                (path.is_file(), path)
+            }
+            Self::CmakeCpp => {
+                let path = solution.join("CMakeLists.txt");
+                (path.is_file(), path)
+            }
+            Self::DotnetCSharp => (has_extension("csproj"), solution.join("*.csproj")),
+            Self::Golang => (has_extension("go"), solution.join("*.go")),
+            Self::Java => {
+                let path = solution.with_extension("java");
+                (path.is_file(), path)
+            }
+            Self::Golfscript
+            | Self::MonoCSharp
+            | Self::Node
+            | Self::Osabie
+            | Self::Perl
+            | Self::Python3 => (solution.is_file(), solution.to_owned()),
+        };
+        (!found).then_some(expected)
+    }
+
+    /// Name shown in the `language` column of the leaderboards.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::CargoRust => "Rust",
+            Self::CmakeCpp => "C++",
+            Self::DotnetCSharp | Self::MonoCSharp => "C#",
+            Self::Golang => "Golang",
+            Self::Golfscript => "golfscript",
+            Self::Java => "Java",
+            Self::Node => "NodeJS",
+            Self::Osabie => "05AB1E",
+            Self::Perl => "Perl",
+            Self::Python3 => "Python3",
+        }
+    }
+
+    /// Whether the solution path is a project directory (vs. a single source file).
+    pub fn is_project_dir(self) -> bool {
+        matches!(
+            self,
+            Self::CargoRust | Self::CmakeCpp | Self::DotnetCSharp | Self::Golang
+        )
+    }
+
+    /// The `{username}` the solution belongs to.
+    pub fn author(self, solution: &Path) -> String {
+        let dir = if self.is_project_dir() {
+            Some(solution)
+        } else {
+            solution.parent()
+        };
+        dir.and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Cleans and compiles the solution, returning the compile time. Compile failures are not
+    /// errors here (same as the scripts); they show up as failed runs instead.
+    pub fn build(self, solution: &Path) -> Result<Duration> {
+        let steps: Vec<(Command, Duration)> = match self {
+            Self::CargoRust => {
+                remove_dir(&solution.join("target"))?;
+                let mut cmd = Command::new("cargo");
+                cmd.arg("build")
+                    .arg("--manifest-path")
+                    .arg(solution.join("Cargo.toml"))
+                    .arg("--release");
+                vec![(cmd, BUILD_TIMEOUT_LONG)]
+            }
+            Self::CmakeCpp => {
+                let build_dir = solution.join("build");
+                remove_dir(&build_dir)?;
+                std::fs::create_dir_all(&build_dir)?;
+                let mut cmake = Command::new("cmake");
+                cmake
+                    .args(["-G", "Ninja"])
+                    .arg("-DCMAKE_CXX_COMPILER_LAUNCHER=ccache")
+                    .arg("-DCMAKE_BUILD_TYPE=Release")
+                    .arg("..")
+                    .current_dir(&build_dir);
+                let mut ninja = Command::new("ninja");
+                ninja.current_dir(&build_dir);
+                vec![(cmake, BUILD_TIMEOUT_LONG), (ninja, BUILD_TIMEOUT_LONG)]
+            }
+            Self::DotnetCSharp => {
+                remove_dir(&solution.join("obj"))?;
+                remove_dir(&solution.join("bin"))?;
+                let mut cmd = Command::new("dotnet");
+                cmd.args(["build", "--configuration", "Release"])
+                    .arg(solution);
+                vec![(cmd, BUILD_TIMEOUT_LONG)]
+            }
+            Self::Golang => {
+                let build_dir = solution.join("build");
+                remove_dir(&build_dir)?;
+                std::fs::create_dir_all(&build_dir)?;
+                let mut cmd = Command::new("go");
+                cmd.arg("build")
+                    .arg(std::path::absolute(solution)?)
+                    .current_dir(&build_dir);
+                vec![(cmd, BUILD_TIMEOUT_SHORT)]
+            }
+            Self::Java => {
+                let _ = std::fs::remove_file(solution.with_extension("class"));
+                let mut cmd = Command::new("javac");
+                cmd.arg(solution.with_extension("java"));
+                vec![(cmd, BUILD_TIMEOUT_SHORT)]
+            }
+            Self::MonoCSharp => {
+                let out = mono_executable(solution);
+                let _ = std::fs::remove_file(&out);
+                let mut cmd = Command::new("mcs");
+                cmd.arg(format!("-out:{}", out.display())).arg(solution);
+                vec![(cmd, BUILD_TIMEOUT_LONG)]
+            }
+            Self::Golfscript | Self::Node | Self::Osabie | Self::Perl | Self::Python3 => {
+                return Ok(Duration::ZERO);
+            }
+        };
+
+        let start = Instant::now();
+        for (mut cmd, timeout) in steps {
+            let mut child = match cmd
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+            {
+                Ok(child) => child,
+                // Missing toolchain, the run will fail.
+                Err(_) => break,
+            };
+            if child.wait_timeout(timeout)?.is_none() {
+                child.kill()?;
+                child.wait()?;
+                break;
+            }
+        }
+        Ok(start.elapsed())
+    }
+
+    /// Command to run the built solution on `input`. Stdin is set up by the caller.
+    pub fn command(self, solution: &Path, input: &Path) -> Result<Command> {
+        let cmd = match self {
+            Self::CargoRust => {
+                let mut cmd = Command::new(solution.join("target/release/out"));
+                cmd.arg(input);
+                cmd
+            }
+            Self::CmakeCpp => {
+                let mut cmd = Command::new(solution.join("build/out"));
+                cmd.arg(input);
+                cmd
+            }
+            Self::DotnetCSharp => {
+                let name = solution.file_name().unwrap_or_default();
+                Command::new(solution.join("bin/Release/net6.0").join(name))
+            }
+            Self::Golang => {
+                let build_dir = solution.join("build");
+                let exe = std::fs::read_dir(&build_dir)
+                    .with_context(|| format!("Failed to read {}.", build_dir.display()))?
+                    .next()
+                    .transpose()?
+                    .map(|entry| entry.path())
+                    .unwrap_or(build_dir);
+                Command::new(exe)
+            }
+            Self::Java => {
+                let mut cmd = Command::new("java");
+                cmd.arg(solution.file_name().unwrap_or_default());
+                if let Some(dir) = solution.parent() {
+                    cmd.current_dir(dir);
+                }
+                cmd
+            }
+            Self::MonoCSharp => {
+                let mut cmd = Command::new("mono");
+                cmd.arg(mono_executable(solution));
+                cmd
+            }
+            Self::Golfscript | Self::Node | Self::Osabie | Self::Perl => {
+                let mut cmd = Command::new(self.script());
+                cmd.arg(solution);
+                cmd
+            }
+            Self::Python3 => {
+                let mut cmd = Command::new("python3");
+                // Flush each answer line as it's printed, for per-part timings.
+                cmd.arg(solution).env("PYTHONUNBUFFERED", "1");
+                cmd
+            }
+        };
+        Ok(cmd)
+    }
+
+    /// Command printing the version of the compiler or interpreter, on stdout or stderr.
+    pub fn version_command(self) -> Command {
+        let (program, args): (_, &[_]) = match self {
+            Self::CargoRust => ("rustc", &["--version"]),
+            Self::CmakeCpp => ("c++", &["--version"]),
+            Self::DotnetCSharp => ("dotnet", &["--version"]),
+            Self::Golang => ("go", &["version"]),
+            // Golfscript is a Ruby script.
+            Self::Golfscript => ("ruby", &["--version"]),
+            Self::Java => ("java", &["-version"]),
+            Self::MonoCSharp => ("mono", &["--version"]),
+            Self::Node => ("node", &["--version"]),
+            // 05AB1E is an Elixir escript.
+            Self::Osabie => ("elixir", &["--version"]),
+            Self::Perl => ("perl", &["-e", "print $^V"]),
+            Self::Python3 => ("python3", &["--version"]),
+        };
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        cmd
+    }
+
+    /// Golfscript gets its input with `\r` stripped.
+    pub fn strips_cr(self) -> bool {
+        matches!(self, Self::Golfscript)
+    }
+}
+
+const BUILD_TIMEOUT_LONG: Duration = Duration::from_secs(120);
+const BUILD_TIMEOUT_SHORT: Duration = Duration::from_secs(20);
+
+fn mono_executable(solution: &Path) -> PathBuf {
+    solution.parent().unwrap_or(Path::new(".")).join("out.exe")
+}
+
+fn remove_dir(dir: &Path) -> Result<()> {
+    match std::fs::remove_dir_all(dir) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+            Err(err).with_context(|| format!("Failed to remove {}.", dir.display()))
+        }
+        _ => Ok(()),
+    }
+}
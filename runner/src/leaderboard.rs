@@ -0,0 +1,508 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use crate::git;
+use crate::toolchain::Toolchains;
+
+const TOOLCHAIN_PREFIX: &str = "toolchain ";
+const ERROR_PREFIX: &str = "error ";
+const PARTS: &str = "parts:";
+const UNVERIFIED: &str = "unverified:";
+const SOLVED: &str = "solved:";
+
+/// What to order leaderboard entries by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Rank {
+    /// Total runtime, like `make test`.
+    #[default]
+    Runtime,
+    /// Most stars on the private leaderboard, then the earliest last star.
+    Solved,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Passed,
+    /// Wrong answer, crash or timeout on `input`.
+    Failed {
+        input: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub language: String,
+    pub author: String,
+    pub compile_ms: u64,
+    /// Time to run all inputs, or the time spent on the failing input.
+    pub total_ms: u64,
+    pub average_ms: u64,
+    pub status: Status,
+    /// Totals of each repeated run, empty when only the text leaderboard was available.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub samples_ms: Vec<u64>,
+    /// Time until each answer line arrived on stdout, per part (line 1 from process start, later
+    /// lines from the line before), summed over inputs. Empty if unknown, e.g. when the output
+    /// was block-buffered.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts_ms: Vec<u64>,
+    /// Inputs without an `.output`, which were run but whose answers could not be checked.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unverified: Vec<String>,
+    /// Version of the compiler or interpreter that produced this entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<String>,
+    /// Seconds from the puzzle unlocking until the author got each star, from the imported
+    /// private leaderboard. Empty if unknown.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub solved_s: Vec<u64>,
+}
+impl Entry {
+    pub fn passed(&self) -> bool {
+        self.status == Status::Passed
+    }
+
+    /// Key used to match entries across leaderboards.
+    pub fn key(&self) -> (&str, &str) {
+        (&self.language, &self.author)
+    }
+
+    /// Sample standard deviation of `samples_ms`, zero if there are fewer than two samples.
+    pub fn stddev_ms(&self) -> f64 {
+        let n = self.samples_ms.len();
+        if n < 2 {
+            return 0.0;
+        }
+        let mean = self.samples_ms.iter().sum::<u64>() as f64 / n as f64;
+        let var = self
+            .samples_ms
+            .iter()
+            .map(|&x| (x as f64 - mean).powi(2))
+            .sum::<f64>()
+            / (n - 1) as f64;
+        var.sqrt()
+    }
+
+    fn to_line(&self) -> String {
+        let mut line = match &self.status {
+            Status::Passed => {
+                let mut line = format!(
+                    "{:<10} {:<15} {:<10} {:<10} {:<10} ✅",
+                    self.language,
+                    self.author,
+                    format_ms(self.compile_ms),
+                    format_ms(self.total_ms),
+                    format_ms(self.average_ms),
+                );
+                if !self.parts_ms.is_empty() {
+                    let parts: Vec<_> = self.parts_ms.iter().map(|&ms| format_ms(ms)).collect();
+                    write!(line, " {} {}", PARTS, parts.join(" ")).unwrap();
+                }
+                if !self.unverified.is_empty() {
+                    write!(line, " {} {}", UNVERIFIED, self.unverified.join(" ")).unwrap();
+                }
+                line
+            }
+            Status::Failed { input } => format!(
+                "{:<10} {:<15} {:<10} {:<21} ❌ {}",
+                self.language,
+                self.author,
+                format_ms(self.compile_ms),
+                format_ms(self.total_ms),
+                input,
+            ),
+        };
+        if !self.solved_s.is_empty() {
+            let solved: Vec<_> = self.solved_s.iter().map(|&s| format_hms(s)).collect();
+            write!(line, " {} {}", SOLVED, solved.join(" ")).unwrap();
+        }
+        line
+    }
+
+    fn from_line(line: &str) -> Result<Self> {
+        let mut fields: Vec<_> = line.split_whitespace().collect();
+        let mut solved_s = Vec::new();
+        if let Some(i) = fields.iter().position(|&field| field == SOLVED) {
+            solved_s = fields[i + 1..]
+                .iter()
+                .map(|&time| parse_hms(time))
+                .collect::<Result<_>>()?;
+            fields.truncate(i);
+        }
+        let mut parts_ms = Vec::new();
+        if let Some(i) = fields.iter().position(|&field| field == PARTS) {
+            let end = fields[i..]
+                .iter()
+                .position(|&field| field == UNVERIFIED)
+                .map_or(fields.len(), |len| i + len);
+            parts_ms = fields[i + 1..end]
+                .iter()
+                .map(|&time| parse_ms(time))
+                .collect::<Result<_>>()?;
+            fields.drain(i..end);
+        }
+        let (language, author, compile, total) = match fields[..] {
+            [language, author, compile, total, ..] => (language, author, compile, total),
+            _ => bail!("Not enough columns in leaderboard line: {}", line),
+        };
+        let compile_ms = parse_ms(compile)?;
+        let total_ms = parse_ms(total)?;
+        let (average_ms, status, unverified) = match fields[4..] {
+            [average, "✅"] => (parse_ms(average)?, Status::Passed, Vec::new()),
+            [average, "✅", UNVERIFIED, ref inputs @ ..] => (
+                parse_ms(average)?,
+                Status::Passed,
+                inputs.iter().map(|&input| input.to_owned()).collect(),
+            ),
+            ["❌", input] => (
+                0,
+                Status::Failed {
+                    input: input.to_owned(),
+                },
+                Vec::new(),
+            ),
+            _ => bail!("Unknown status in leaderboard line: {}", line),
+        };
+        Ok(Self {
+            language: language.to_owned(),
+            author: author.to_owned(),
+            compile_ms,
+            total_ms,
+            average_ms,
+            status,
+            samples_ms: Vec::new(),
+            parts_ms,
+            unverified,
+            toolchain: None,
+            solved_s,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub year: u32,
+    pub day: u32,
+    /// Puzzle title from the day's `README.md`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Toolchain versions of the languages in this leaderboard, keyed by `lang/*.sh` name.
+    #[serde(default, skip_serializing_if = "Toolchains::is_empty")]
+    pub toolchains: Toolchains,
+    /// Problems with the day's io files, e.g. an `.output` without `.input`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+    pub entries: Vec<Entry>,
+}
+impl Leaderboard {
+    pub fn txt_path(dir: &Path, year: u32, day: u32) -> PathBuf {
+        dir.join(year.to_string()).join(format!("{}.txt", day))
+    }
+    pub fn json_path(dir: &Path, year: u32, day: u32) -> PathBuf {
+        dir.join(year.to_string()).join(format!("{}.json", day))
+    }
+
+    /// Sorts entries the same way `make test` does (`sort -n` on the total column).
+    pub fn sort(&mut self) {
+        self.sort_by(Rank::Runtime);
+    }
+
+    /// Sorts entries by `rank`. When ranking by solve time, more stars come first, then the
+    /// earlier last star, so entries without stars go last. Ties (e.g. one author's solutions
+    /// in several languages) are broken by runtime.
+    pub fn sort_by(&mut self, rank: Rank) {
+        self.entries.sort_by_cached_key(|entry| {
+            let solved = match rank {
+                Rank::Runtime => None,
+                Rank::Solved => Some((
+                    Reverse(entry.solved_s.len()),
+                    entry.solved_s.last().copied().unwrap_or(u64::MAX),
+                )),
+            };
+            (solved, entry.total_ms, entry.to_line())
+        });
+    }
+
+    pub fn get(&self, key: (&str, &str)) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.key() == key)
+    }
+
+    /// Renders the `leaderboards/{year}/{day}.txt` format.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out).unwrap();
+        match &self.title {
+            Some(title) => writeln!(out, "-- Day {}: {} --", self.day, title).unwrap(),
+            None => writeln!(out, "-- Day {} --", self.day).unwrap(),
+        }
+        for (script, version) in &self.toolchains {
+            writeln!(
+                out,
+                "{:<10} {:<15} {}",
+                TOOLCHAIN_PREFIX.trim_end(),
+                script,
+                version
+            )
+            .unwrap();
+        }
+        for error in &self.errors {
+            writeln!(out, "{:<10} {} ❌", ERROR_PREFIX.trim_end(), error).unwrap();
+        }
+        writeln!(
+            out,
+            "{:<10} {:<15} {:<10} {:<10} {:<10} {:<10} ",
+            "language", "author", "compile", "total", "average", "status"
+        )
+        .unwrap();
+        for entry in &self.entries {
+            writeln!(out, "{}", entry.to_line()).unwrap();
+        }
+        out
+    }
+
+    pub fn parse_text(year: u32, text: &str) -> Result<Self> {
+        let mut day = None;
+        let mut title = None;
+        let mut toolchains = Toolchains::new();
+        let mut errors = Vec::new();
+        let mut entries = Vec::new();
+        for line in text.lines() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with("language ") {
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("-- Day ") {
+                let rest = rest.trim_end_matches(" --");
+                let num = match rest.split_once(": ") {
+                    Some((num, name)) => {
+                        title = Some(name.to_owned());
+                        num
+                    }
+                    None => rest,
+                };
+                day = Some(num.parse().with_context(|| format!("Bad day: {}", line))?);
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix(TOOLCHAIN_PREFIX) {
+                let (script, version) = rest
+                    .trim_start()
+                    .split_once(' ')
+                    .ok_or_else(|| anyhow!("Bad toolchain line: {}", line))?;
+                toolchains.insert(script.to_owned(), version.trim_start().to_owned());
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix(ERROR_PREFIX) {
+                errors.push(rest.trim_start().trim_end_matches(" ❌").to_owned());
+                continue;
+            }
+            entries.push(Entry::from_line(line)?);
+        }
+        Ok(Self {
+            year,
+            day: day.ok_or_else(|| anyhow!("Missing `-- Day N --` header."))?,
+            title,
+            toolchains,
+            errors,
+            entries,
+        })
+    }
+
+    /// Loads `{dir}/{year}/{day}.json`, falling back to the `.txt` leaderboard. Returns `None`
+    /// if neither exists or the text leaderboard is empty.
+    pub fn load(dir: &Path, year: u32, day: u32) -> Result<Option<Self>> {
+        let read = |path: PathBuf| -> Result<Option<(String, String)>> {
+            if !path.is_file() {
+                return Ok(None);
+            }
+            let contents = std::fs::read_to_string(&path)?;
+            Ok(Some((path.display().to_string(), contents)))
+        };
+        Self::from_sources(
+            year,
+            read(Self::json_path(dir, year, day))?,
+            read(Self::txt_path(dir, year, day))?,
+        )
+    }
+
+    /// Loads the leaderboard for `year`/`day` as committed at git revision `rev`.
+    pub fn load_rev(root: &Path, rev: &str, year: u32, day: u32) -> Result<Option<Self>> {
+        let show =
+            |path: PathBuf| -> Result<Option<(String, String)>> {
+                let path = path.to_string_lossy().into_owned();
+                Ok(git::show(root, rev, &path)?
+                    .map(|contents| (format!("{}:{}", rev, path), contents)))
+            };
+        let dir = Path::new("leaderboards");
+        Self::from_sources(
+            year,
+            show(Self::json_path(dir, year, day))?,
+            show(Self::txt_path(dir, year, day))?,
+        )
+    }
+
+    /// Years and days which have a leaderboard at git revision `rev`.
+    pub fn list_rev(root: &Path, rev: &str) -> Result<BTreeSet<(u32, u32)>> {
+        let mut days = BTreeSet::new();
+        for year in git::ls_tree(root, rev, "leaderboards")? {
+            let Ok(year) = year.parse() else {
+                continue;
+            };
+            for file in git::ls_tree(root, rev, &format!("leaderboards/{}", year))? {
+                let day = file
+                    .strip_suffix(".json")
+                    .or_else(|| file.strip_suffix(".txt"))
+                    .and_then(|day| day.parse().ok());
+                if let Some(day) = day {
+                    days.insert((year, day));
+                }
+            }
+        }
+        Ok(days)
+    }
+
+    /// Prefers the JSON leaderboard over the text one. Sources are `(name, contents)`.
+    fn from_sources(
+        year: u32,
+        json: Option<(String, String)>,
+        txt: Option<(String, String)>,
+    ) -> Result<Option<Self>> {
+        if let Some((name, json)) = json {
+            let leaderboard = serde_json::from_str(&json)
+                .with_context(|| format!("Failed to parse {}.", name))?;
+            return Ok(Some(leaderboard));
+        }
+        match txt {
+            Some((_, text)) if text.trim().is_empty() => Ok(None),
+            Some((name, text)) => Self::parse_text(year, &text)
+                .with_context(|| format!("Failed to parse {}.", name))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Writes only the `.txt` leaderboard.
+    pub fn save_txt(&self, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir.join(self.year.to_string()))?;
+        std::fs::write(Self::txt_path(dir, self.year, self.day), self.to_text())?;
+        Ok(())
+    }
+
+    /// Writes both the `.txt` and `.json` leaderboards.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        self.save_txt(dir)?;
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        std::fs::write(Self::json_path(dir, self.year, self.day), json)?;
+        Ok(())
+    }
+}
+
+pub fn format_ms(ms: u64) -> String {
+    format!("{}ms", ms)
+}
+
+fn parse_ms(s: &str) -> Result<u64> {
+    s.strip_suffix("ms")
+        .and_then(|num| num.parse().ok())
+        .ok_or_else(|| anyhow!("Bad time `{}`.", s))
+}
+
+/// `H:MM:SS`, hours aren't wrapped into days.
+pub fn format_hms(s: u64) -> String {
+    format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+}
+
+fn parse_hms(s: &str) -> Result<u64> {
+    let parts: Option<Vec<u64>> = s.split(':').map(|part| part.parse().ok()).collect();
+    match parts.as_deref() {
+        Some(&[h, m, s]) if m < 60 && s < 60 => Ok(h * 3600 + m * 60 + s),
+        _ => bail!("Bad time `{}`.", s),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DAY_3: &str = "
+-- Day 3 --
+language   author          compile    total      average    status
+Rust       mingweisamuel   4040ms     67ms       6ms        ✅
+Python3    slate           0ms        215ms      36ms       ✅
+NodeJS     stelar7         0ms        20012ms               ❌ /test/2022/3/io/stelar7.input
+";
+
+    #[test]
+    fn test_text_roundtrip() {
+        let mut leaderboard = Leaderboard::parse_text(2022, DAY_3).unwrap();
+        assert_eq!(3, leaderboard.day);
+        assert_eq!(3, leaderboard.entries.len());
+        assert_eq!(
+            Status::Failed {
+                input: "/test/2022/3/io/stelar7.input".to_owned()
+            },
+            leaderboard.entries[2].status
+        );
+        let text = leaderboard.to_text();
+        assert!(text.contains(
+            "NodeJS     stelar7         0ms        20012ms               ❌ /test/2022/3/io/stelar7.input\n"
+        ));
+        assert_eq!(leaderboard, Leaderboard::parse_text(2022, &text).unwrap());
+
+        leaderboard
+            .toolchains
+            .insert("cargo_rust".to_owned(), "rustc 1.65.0".to_owned());
+        let text = leaderboard.to_text();
+        assert!(text.contains("\n-- Day 3 --\ntoolchain  cargo_rust      rustc 1.65.0\nlanguage "));
+        assert_eq!(leaderboard, Leaderboard::parse_text(2022, &text).unwrap());
+
+        leaderboard
+            .errors
+            .push("2022/3/io/a.output has no matching .input".to_owned());
+        leaderboard.entries[0].unverified = vec!["2022/3/io/b.input".to_owned()];
+        let text = leaderboard.to_text();
+        assert!(text.contains("6ms        ✅ unverified: 2022/3/io/b.input\n"));
+        assert_eq!(leaderboard, Leaderboard::parse_text(2022, &text).unwrap());
+
+        leaderboard.entries[0].parts_ms = vec![2, 65];
+        let text = leaderboard.to_text();
+        assert!(text.contains("6ms        ✅ parts: 2ms 65ms unverified: 2022/3/io/b.input\n"));
+        assert_eq!(leaderboard, Leaderboard::parse_text(2022, &text).unwrap());
+
+        leaderboard.entries[0].solved_s = vec![312, 90061];
+        leaderboard.entries[2].solved_s = vec![100];
+        let text = leaderboard.to_text();
+        assert!(text.contains(
+            "✅ parts: 2ms 65ms unverified: 2022/3/io/b.input solved: 0:05:12 25:01:01\n"
+        ));
+        assert!(text.contains("stelar7.input solved: 0:01:40\n"));
+        assert_eq!(leaderboard, Leaderboard::parse_text(2022, &text).unwrap());
+    }
+
+    #[test]
+    fn test_sort_by() {
+        let mut leaderboard = Leaderboard::parse_text(2022, DAY_3).unwrap();
+        leaderboard.entries[1].solved_s = vec![50, 700];
+        leaderboard.entries[2].solved_s = vec![600];
+        leaderboard.sort_by(Rank::Solved);
+        let authors: Vec<_> = leaderboard.entries.iter().map(|e| &*e.author).collect();
+        // Both stars at 700s beat one star at 600s.
+        assert_eq!(vec!["slate", "stelar7", "mingweisamuel"], authors);
+        leaderboard.sort();
+        let authors: Vec<_> = leaderboard.entries.iter().map(|e| &*e.author).collect();
+        assert_eq!(vec!["mingweisamuel", "slate", "stelar7"], authors);
+    }
+
+    #[test]
+    fn test_stddev() {
+        let mut entry = Leaderboard::parse_text(2022, DAY_3).unwrap().entries[0].clone();
+        assert_eq!(0.0, entry.stddev_ms());
+        entry.samples_ms = vec![2, 4, 4, 4, 5, 5, 7, 9];
+        assert!((entry.stddev_ms() - 2.138).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,275 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use toml_edit::DocumentMut;
+
+/// Expected `.output` line counts, relative to the root.
+pub const ANSWERS: &str = "answers.toml";
+
+/// Lines in an `.output` without an entry in `answers.toml`, one per part.
+const DEFAULT_ANSWER_LINES: usize = 2;
+
+const BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// Expected number of lines of each `.output`.
+#[derive(Debug, Clone, Default)]
+pub struct AnswerLines(BTreeMap<String, usize>);
+impl AnswerLines {
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(ANSWERS);
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}.", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Failed to parse {}.", path.display()))
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let doc: DocumentMut = text.parse()?;
+        doc.iter()
+            .map(|(key, item)| {
+                let lines = item
+                    .as_integer()
+                    .and_then(|lines| usize::try_from(lines).ok())
+                    .ok_or_else(|| anyhow!("`{}` must be a line count.", key))?;
+                Ok((key.to_owned(), lines))
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    /// For `{year}/{day}/io/{name}.output`.
+    pub fn expected(&self, year: u32, day: u32, name: &str) -> usize {
+        let day_key = format!("{}/{}", year, day);
+        let file_key = format!("{}/{}", day_key, name);
+        self.0
+            .get(&file_key)
+            .or_else(|| self.0.get(&day_key))
+            .copied()
+            .unwrap_or(DEFAULT_ANSWER_LINES)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    Bom,
+    Crlf,
+    MissingTrailingNewline,
+    /// 1-based line numbers. Only checked in `.output`s, as puzzle inputs (2022/5's crate
+    /// stacks) may need theirs.
+    TrailingWhitespace(Vec<usize>),
+    TrailingBlankLines,
+    AnswerLines {
+        expected: usize,
+        actual: usize,
+    },
+}
+impl Issue {
+    pub fn fixable(&self) -> bool {
+        !matches!(self, Self::AnswerLines { .. })
+    }
+}
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bom => write!(f, "starts with a byte order mark"),
+            Self::Crlf => write!(f, "has CRLF line endings"),
+            Self::MissingTrailingNewline => write!(f, "missing trailing newline"),
+            Self::TrailingWhitespace(lines) => {
+                let lines: Vec<_> = lines.iter().map(usize::to_string).collect();
+                write!(f, "trailing whitespace on line(s) {}", lines.join(", "))
+            }
+            Self::TrailingBlankLines => write!(f, "has trailing blank lines"),
+            Self::AnswerLines { expected, actual } => write!(
+                f,
+                "has {} answer line(s), expected {} (see {})",
+                actual, expected, ANSWERS
+            ),
+        }
+    }
+}
+
+/// Applies the mechanical fixes: strips the BOM, converts CRLF, and for outputs strips
+/// trailing whitespace and blank lines, then ensures a trailing newline.
+pub fn fix(contents: &[u8], is_output: bool) -> Vec<u8> {
+    let contents = contents.strip_prefix(BOM).unwrap_or(contents);
+    let mut lines: Vec<&[u8]> = contents
+        .split(|&b| b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .map(|line| {
+            if is_output {
+                line.trim_ascii_end()
+            } else {
+                line
+            }
+        })
+        .collect();
+    // The empty piece after the final `\n`.
+    if lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    if is_output {
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+    }
+    let mut fixed = Vec::with_capacity(contents.len() + 1);
+    for line in lines {
+        fixed.extend_from_slice(line);
+        fixed.push(b'\n');
+    }
+    fixed
+}
+
+/// Checks one io file. `expected_lines` is given for `.output`s.
+pub fn lint_file(contents: &[u8], expected_lines: Option<usize>) -> Vec<Issue> {
+    let is_output = expected_lines.is_some();
+    let mut issues = Vec::new();
+    if contents.starts_with(BOM) {
+        issues.push(Issue::Bom);
+    }
+    if contents.windows(2).any(|w| w == b"\r\n") {
+        issues.push(Issue::Crlf);
+    }
+    if !contents.is_empty() && !contents.ends_with(b"\n") {
+        issues.push(Issue::MissingTrailingNewline);
+    }
+    if is_output {
+        let trailing: Vec<_> = contents
+            .split(|&b| b == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .enumerate()
+            .filter(|(_, line)| line.last().is_some_and(u8::is_ascii_whitespace))
+            .map(|(i, _)| i + 1)
+            .collect();
+        if !trailing.is_empty() {
+            issues.push(Issue::TrailingWhitespace(trailing));
+        }
+        let body = contents.strip_suffix(b"\n").unwrap_or(contents);
+        if body.ends_with(b"\n") || body.ends_with(b"\n\r") {
+            issues.push(Issue::TrailingBlankLines);
+        }
+    }
+    if let Some(expected) = expected_lines {
+        let actual = fix(contents, true).iter().filter(|&&b| b == b'\n').count();
+        if actual != expected {
+            issues.push(Issue::AnswerLines { expected, actual });
+        }
+    }
+    issues
+}
+
+/// Issues of one io file, relative to the root.
+#[derive(Debug, Clone)]
+pub struct FileLint {
+    pub path: PathBuf,
+    pub issues: Vec<Issue>,
+    /// Whether the fixable issues were fixed in place.
+    pub fixed: bool,
+}
+
+/// Lints every `{year}/*/io` (or just `day`'s), fixing files in place with `fix_files`.
+pub fn lint(root: &Path, year: u32, day: Option<u32>, fix_files: bool) -> Result<Vec<FileLint>> {
+    let answers = AnswerLines::load(root)?;
+    let year_dir = root.join(year.to_string());
+    let days = match day {
+        Some(day) => vec![day],
+        // Days without a `test.sh` may still have io.
+        None => {
+            let mut days = std::fs::read_dir(&year_dir)
+                .with_context(|| format!("Failed to read {}.", year_dir.display()))?
+                .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+                .collect::<Vec<u32>>();
+            days.sort_unstable();
+            days
+        }
+    };
+    let mut results = Vec::new();
+    for day in days {
+        let io_dir = year_dir.join(day.to_string()).join("io");
+        let Ok(entries) = std::fs::read_dir(&io_dir) else {
+            // Reported by `validate`.
+            continue;
+        };
+        let mut paths = entries
+            .map(|entry| Ok(entry?.path()))
+            .collect::<Result<Vec<_>>>()?;
+        paths.sort();
+        for path in paths {
+            let expected_lines = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("input") => None,
+                Some("output") => {
+                    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+                    Some(answers.expected(year, day, &name))
+                }
+                _ => continue,
+            };
+            let contents = std::fs::read(&path)
+                .with_context(|| format!("Failed to read {}.", path.display()))?;
+            let issues = lint_file(&contents, expected_lines);
+            if issues.is_empty() {
+                continue;
+            }
+            let fixed = fix_files && issues.iter().any(Issue::fixable);
+            if fixed {
+                std::fs::write(&path, fix(&contents, expected_lines.is_some()))
+                    .with_context(|| format!("Failed to write {}.", path.display()))?;
+            }
+            results.push(FileLint {
+                path: path.strip_prefix(root).unwrap_or(&path).to_owned(),
+                issues,
+                fixed,
+            });
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_answer_lines() {
+        let answers = AnswerLines::parse(
+            r#"
+"2022/10" = 7 # CRT rows
+"2022/17/example" = 1
+"#,
+        )
+        .unwrap();
+        assert_eq!(7, answers.expected(2022, 10, "stelar7"));
+        assert_eq!(1, answers.expected(2022, 17, "example"));
+        assert_eq!(2, answers.expected(2022, 17, "mingweisamuel"));
+        assert!(AnswerLines::parse(r#""2022/10" = "7""#).is_err());
+    }
+
+    #[test]
+    fn test_lint_file() {
+        assert_eq!(Vec::<Issue>::new(), lint_file(b"1\n2\n", Some(2)));
+        assert_eq!(Vec::<Issue>::new(), lint_file(b"    [D]    \n", None));
+
+        let contents = b"\xef\xbb\xbf1 \r\n2\r\n\r\n";
+        assert_eq!(
+            vec![
+                Issue::Bom,
+                Issue::Crlf,
+                Issue::TrailingWhitespace(vec![1]),
+                Issue::TrailingBlankLines,
+            ],
+            lint_file(contents, Some(2))
+        );
+        assert_eq!(b"1\n2\n".to_vec(), fix(contents, true));
+
+        assert_eq!(
+            vec![
+                Issue::MissingTrailingNewline,
+                Issue::AnswerLines {
+                    expected: 2,
+                    actual: 1
+                },
+            ],
+            lint_file(b"1", Some(2))
+        );
+        assert_eq!(b"  a \n\nb\n".to_vec(), fix(b"  a \r\n\r\nb", false));
+    }
+}
@@ -0,0 +1,702 @@
+use anyhow::Result;
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+mod chart;
+mod client;
+mod config;
+mod consensus;
+mod diff;
+mod example;
+mod exec;
+mod git;
+mod history;
+mod html;
+mod lang;
+mod leaderboard;
+mod lint;
+mod manifest;
+mod mock;
+mod perf;
+mod puzzle;
+mod scaffold;
+mod stars;
+mod submit;
+#[cfg(test)]
+mod temp_dir;
+mod toolchain;
+mod validate;
+
+use config::DayConfig;
+use history::{History, RunInfo};
+use lang::Language;
+use leaderboard::{format_ms, Leaderboard, Rank};
+use perf::PerfOptions;
+use toolchain::Toolchains;
+
+#[derive(Parser)]
+#[command(about = "Runs and checks Advent of Code solutions.")]
+struct Cli {
+    /// Repository root.
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+    /// Results database, relative to the root.
+    #[arg(long, default_value = "results.sqlite")]
+    db: PathBuf,
+    /// Don't record results in the database.
+    #[arg(long)]
+    no_history: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct DaySelection {
+    #[arg(long)]
+    year: u32,
+    /// Day to run, all days of the year if omitted.
+    #[arg(long)]
+    day: Option<u32>,
+}
+impl DaySelection {
+    fn days(&self, cli: &Cli) -> Result<Vec<u32>> {
+        match self.day {
+            Some(day) => Ok(vec![day]),
+            None => config::days(&cli.root, self.year),
+        }
+    }
+}
+
+/// Appends the results of this invocation to the results database.
+fn record_history(cli: &Cli, toolchains: &Toolchains, leaderboards: &[Leaderboard]) -> Result<()> {
+    if cli.no_history || leaderboards.is_empty() {
+        return Ok(());
+    }
+    let commit = git::rev_parse(&cli.root, "HEAD").ok();
+    let info = RunInfo::new(commit, toolchains.clone());
+    History::open(&cli.root.join(&cli.db))?.record(&info, leaderboards)?;
+    Ok(())
+}
+
+/// Where and as whom to reach adventofcode.com.
+#[derive(Args)]
+struct ClientArgs {
+    /// Value of the adventofcode.com `session` cookie.
+    #[arg(long, env = "AOC_SESSION", hide_env_values = true)]
+    session: Option<String>,
+    #[arg(long, env = "AOC_BASE_URL", default_value = client::DEFAULT_BASE_URL)]
+    base_url: String,
+    /// Inputs already fetched, relative to the root.
+    #[arg(long, default_value = ".cache/aoc")]
+    cache: PathBuf,
+}
+impl ClientArgs {
+    fn client(&self, cli: &Cli) -> client::Client {
+        client::Client::new(
+            &self.base_url,
+            self.session.clone(),
+            cli.root.join(&self.cache),
+        )
+    }
+}
+
+fn parse_language(name: &str) -> Result<Language, String> {
+    Language::from_name(name).ok_or_else(|| {
+        let names: Vec<_> = Language::ALL.iter().map(|lang| lang.dir_name()).collect();
+        format!("expected one of: {}", names.join(", "))
+    })
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run solutions and print (or write) their leaderboards.
+    Run {
+        #[command(flatten)]
+        selection: DaySelection,
+        /// Times to repeat each solution's inputs.
+        #[arg(long, default_value_t = 1)]
+        runs: usize,
+        /// Write `leaderboards/{year}/{day}.{txt,json}` instead of printing.
+        #[arg(long)]
+        write: bool,
+        /// Order of the entries, solve times come from `import-stars`.
+        #[arg(long, value_enum, default_value_t)]
+        rank: Rank,
+    },
+    /// Re-run solutions and fail if any got slower than the committed leaderboards.
+    CheckPerf {
+        #[command(flatten)]
+        selection: DaySelection,
+        /// Times to repeat each solution's inputs, to measure variance.
+        #[arg(long, default_value_t = 5)]
+        runs: usize,
+        /// Allowed relative slowdown.
+        #[arg(long, default_value_t = 0.5)]
+        threshold: f64,
+        /// Standard deviations of noise tolerated on top of the threshold.
+        #[arg(long, default_value_t = 2.0)]
+        sigma: f64,
+        /// Slowdowns below this many milliseconds are ignored.
+        #[arg(long, default_value_t = 5)]
+        min_delta_ms: u64,
+    },
+    /// Compare the committed leaderboards of two git revisions.
+    Diff {
+        /// Old revision.
+        from: String,
+        /// New revision.
+        #[arg(default_value = "HEAD")]
+        to: String,
+        /// Only compare this year.
+        #[arg(long)]
+        year: Option<u32>,
+        /// Only compare this day.
+        #[arg(long, requires = "year")]
+        day: Option<u32>,
+    },
+    /// Print the recorded runtime history of one solution.
+    History {
+        #[arg(long)]
+        year: u32,
+        #[arg(long)]
+        day: u32,
+        #[arg(long)]
+        author: String,
+        /// Language column, e.g. `Rust`, needed if the author has several solutions that day.
+        #[arg(long)]
+        language: Option<String>,
+        /// Write an SVG chart to this path instead of printing a table.
+        #[arg(long)]
+        svg: Option<PathBuf>,
+    },
+    /// Write missing `.output` files that enough independent passing solutions agree on.
+    Consensus {
+        #[command(flatten)]
+        selection: DaySelection,
+        /// Distinct authors that must agree.
+        #[arg(long, default_value_t = 3)]
+        min_authors: usize,
+        /// Distinct languages that must agree.
+        #[arg(long, default_value_t = 2)]
+        min_languages: usize,
+        /// Only report, don't write any files.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Check Rust solutions' `Cargo.toml` for the `out` binary, the release profile and
+    /// vendored dependencies.
+    CheckManifests {
+        /// Only check this year.
+        #[arg(long)]
+        year: Option<u32>,
+        /// Only check this day.
+        #[arg(long, requires = "year")]
+        day: Option<u32>,
+        /// Normalize the binary name and release profile in place.
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Check io files for BOMs, CRLF, trailing whitespace and newlines and answer line counts.
+    Lint {
+        /// Only check this year.
+        #[arg(long)]
+        year: Option<u32>,
+        /// Only check this day.
+        #[arg(long, requires = "year")]
+        day: Option<u32>,
+        /// Fix everything but answer line counts in place.
+        #[arg(long)]
+        fix: bool,
+    },
+    /// List the puzzles parsed from each day's `README.md`.
+    Puzzles {
+        /// Write the index to `puzzles.json`.
+        #[arg(long)]
+        write: bool,
+    },
+    /// Extract each day's example input from its `README.md` and smoke-test the solutions on it.
+    Examples {
+        #[command(flatten)]
+        selection: DaySelection,
+        /// Use this code block of part one instead of guessing.
+        #[arg(long, requires = "day")]
+        block: Option<usize>,
+        /// Write `io/example.input` where it's missing.
+        #[arg(long)]
+        write: bool,
+        /// Only extract, don't run the solutions.
+        #[arg(long)]
+        no_run: bool,
+    },
+    /// Download a day's puzzle input into `io/{username}.input` and its description into
+    /// `README.md`.
+    Fetch {
+        #[arg(long)]
+        year: u32,
+        #[arg(long)]
+        day: u32,
+        /// Whose input to fetch, the session cookie must belong to them.
+        #[arg(long)]
+        username: String,
+        #[command(flatten)]
+        client: ClientArgs,
+    },
+    /// Import a private leaderboard's JSON export into `leaderboards/{year}/stars.json`, for
+    /// `show` and `run` to add the solve times to the leaderboards.
+    ImportStars {
+        /// The `/{year}/leaderboard/private/view/{id}.json` export.
+        file: PathBuf,
+    },
+    /// Print the committed leaderboards with the solve times from `import-stars`.
+    Show {
+        #[command(flatten)]
+        selection: DaySelection,
+        /// Order of the entries.
+        #[arg(long, value_enum, default_value_t)]
+        rank: Rank,
+    },
+    /// Run a solution on its author's input, submit the answers and record them in
+    /// `io/{username}.output` once they're right.
+    Submit {
+        #[arg(long)]
+        year: u32,
+        #[arg(long)]
+        day: u32,
+        /// Whose solution and input to submit, the session cookie must belong to them.
+        #[arg(long)]
+        username: String,
+        /// Language directory or script name, needed if the author has several solutions.
+        #[arg(long, value_parser = parse_language)]
+        language: Option<Language>,
+        /// Seconds to wait out a rate limit before giving up.
+        #[arg(long, default_value_t = 60)]
+        max_wait: u64,
+        #[command(flatten)]
+        client: ClientArgs,
+    },
+    /// Serve fixtures like adventofcode.com, to use `fetch` offline with `--base-url`.
+    MockServer {
+        /// `{year}/day/{day}/index.html` and `{year}/day/{day}/input` files.
+        #[arg(long, default_value = "runner/fixtures")]
+        fixtures: PathBuf,
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Seconds answers are rate limited for after a wrong one.
+        #[arg(long, default_value_t = 60)]
+        cooldown: u64,
+    },
+    /// Create a new solution from the language's template and register it in `test.sh`.
+    New {
+        year: u32,
+        day: u32,
+        /// Language directory (`rust`) or script name (`cargo_rust`).
+        #[arg(value_parser = parse_language)]
+        language: Language,
+        username: String,
+    },
+    /// Check the repository layout rules from the README.
+    Validate {
+        /// Only check this year.
+        #[arg(long)]
+        year: Option<u32>,
+        /// Only check this day.
+        #[arg(long, requires = "year")]
+        day: Option<u32>,
+    },
+}
+
+fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+    let leaderboards_dir = cli.root.join("leaderboards");
+    match &cli.command {
+        Command::Run {
+            selection,
+            runs,
+            write,
+            rank,
+        } => {
+            let toolchains = toolchain::probe();
+            let stars = stars::Stars::load(&leaderboards_dir, selection.year)?;
+            let mut leaderboards = Vec::new();
+            for day in selection.days(&cli)? {
+                let config = DayConfig::load(&cli.root, selection.year, day)?;
+                let mut leaderboard = exec::run_day(&config, *runs, &toolchains)?;
+                stars.merge_into(&mut leaderboard);
+                leaderboard.sort_by(*rank);
+                if *write {
+                    leaderboard.save(&leaderboards_dir)?;
+                } else {
+                    print!("{}", leaderboard.to_text());
+                }
+                leaderboards.push(leaderboard);
+            }
+            record_history(&cli, &toolchains, &leaderboards)?;
+            let mut success = true;
+            for leaderboard in &leaderboards {
+                for error in &leaderboard.errors {
+                    eprintln!("Error: {}", error);
+                    success = false;
+                }
+            }
+            Ok(if success {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            })
+        }
+        Command::CheckPerf {
+            selection,
+            runs,
+            threshold,
+            sigma,
+            min_delta_ms,
+        } => {
+            let opts = PerfOptions {
+                threshold: *threshold,
+                sigma: *sigma,
+                min_delta_ms: *min_delta_ms,
+            };
+            let mut regressions = Vec::new();
+            let toolchains = toolchain::probe();
+            let mut leaderboards = Vec::new();
+            for day in selection.days(&cli)? {
+                let Some(baseline) = Leaderboard::load(&leaderboards_dir, selection.year, day)?
+                else {
+                    eprintln!("No leaderboard for {}/{}, skipping.", selection.year, day);
+                    continue;
+                };
+                let config = DayConfig::load(&cli.root, selection.year, day)?;
+                let fresh = exec::run_day(&config, *runs, &toolchains)?;
+                regressions.extend(perf::check(&baseline, &fresh, &opts));
+                leaderboards.push(fresh);
+            }
+            record_history(&cli, &toolchains, &leaderboards)?;
+            for r in &regressions {
+                println!(
+                    "Day {:<2} {:<10} {:<15} {:>10} -> {:<10} ({:.2}x, limit {})",
+                    r.day,
+                    r.language,
+                    r.author,
+                    format_ms(r.baseline_ms),
+                    format_ms(r.fresh_ms),
+                    r.slowdown(),
+                    format_ms(r.limit_ms.round() as u64),
+                );
+                if !r.baseline_parts_ms.is_empty() && !r.fresh_parts_ms.is_empty() {
+                    let parts = |parts_ms: &[u64]| {
+                        let parts: Vec<_> = parts_ms.iter().map(|&ms| format_ms(ms)).collect();
+                        parts.join(" ")
+                    };
+                    println!(
+                        "       parts {} -> {}",
+                        parts(&r.baseline_parts_ms),
+                        parts(&r.fresh_parts_ms)
+                    );
+                }
+            }
+            if regressions.is_empty() {
+                println!("No performance regressions.");
+                Ok(ExitCode::SUCCESS)
+            } else {
+                println!("{} performance regression(s).", regressions.len());
+                Ok(ExitCode::FAILURE)
+            }
+        }
+        Command::Diff {
+            from,
+            to,
+            year,
+            day,
+        } => {
+            let from = git::rev_parse(&cli.root, from)?;
+            let to = git::rev_parse(&cli.root, to)?;
+            let mut days = Leaderboard::list_rev(&cli.root, &from)?;
+            days.extend(Leaderboard::list_rev(&cli.root, &to)?);
+            days.retain(|&(y, d)| {
+                year.is_none_or(|year| y == year) && day.is_none_or(|day| d == day)
+            });
+            for (year, day) in days {
+                let before = Leaderboard::load_rev(&cli.root, &from, year, day)?;
+                let after = Leaderboard::load_rev(&cli.root, &to, year, day)?;
+                let diffs = diff::diff(before.as_ref(), after.as_ref());
+                if !diffs.is_empty() {
+                    println!("{}", diff::to_text(year, day, &diffs));
+                }
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::History {
+            year,
+            day,
+            author,
+            language,
+            svg,
+        } => {
+            let history = History::open(&cli.root.join(&cli.db))?;
+            let rows = history.query(*year, *day, author, language.as_deref())?;
+            if rows.is_empty() {
+                anyhow::bail!("No recorded results for {} on {}/{}.", author, year, day);
+            }
+            let mut languages: Vec<_> = rows.iter().map(|row| &*row.entry.language).collect();
+            languages.sort_unstable();
+            languages.dedup();
+            if 1 < languages.len() {
+                anyhow::bail!(
+                    "{} has results in several languages on {}/{}: {}. Pick one with --language.",
+                    author,
+                    year,
+                    day,
+                    languages.join(", ")
+                );
+            }
+            if let Some(svg) = svg {
+                let title = format!("{}/{} {}", year, day, author);
+                std::fs::write(svg, chart::runtime_svg(&title, &rows))?;
+                return Ok(ExitCode::SUCCESS);
+            }
+            println!(
+                "{:<20} {:<8} {:<10} {:<10} {:<10} {:<10} {:<6} parts",
+                "date", "commit", "language", "compile", "total", "average", "status"
+            );
+            for row in rows {
+                let commit = row.commit.as_deref().unwrap_or("-");
+                let parts: Vec<_> = row.entry.parts_ms.iter().map(|&ms| format_ms(ms)).collect();
+                println!(
+                    "{:<20} {:<8} {:<10} {:<10} {:<10} {:<10} {:<5} {}",
+                    row.datetime,
+                    &commit[..commit.len().min(7)],
+                    row.entry.language,
+                    format_ms(row.entry.compile_ms),
+                    format_ms(row.entry.total_ms),
+                    format_ms(row.entry.average_ms),
+                    if row.entry.passed() { "✅" } else { "❌" },
+                    parts.join(" "),
+                );
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Consensus {
+            selection,
+            min_authors,
+            min_languages,
+            dry_run,
+        } => {
+            let opts = consensus::ConsensusOptions {
+                min_authors: *min_authors,
+                min_languages: *min_languages,
+            };
+            let mut success = true;
+            for day in selection.days(&cli)? {
+                let config = DayConfig::load(&cli.root, selection.year, day)?;
+                success &= consensus::run(&config, &opts, *dry_run)?;
+            }
+            Ok(if success {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            })
+        }
+        Command::CheckManifests { year, day, fix } => {
+            let mut errors = 0;
+            for (path, result) in manifest::check_all(&cli.root, *year, *day, *fix)? {
+                for message in &result.fixed {
+                    println!("{}: fixed: {}", path.display(), message);
+                }
+                for message in &result.warnings {
+                    println!("{}: warning: {}", path.display(), message);
+                }
+                for message in &result.errors {
+                    println!("{}: {}", path.display(), message);
+                }
+                errors += result.errors.len();
+            }
+            if errors == 0 {
+                println!("No violations.");
+                Ok(ExitCode::SUCCESS)
+            } else {
+                println!("{} violation(s).", errors);
+                Ok(ExitCode::FAILURE)
+            }
+        }
+        Command::Lint { year, day, fix } => {
+            let years = match year {
+                Some(year) => vec![*year],
+                None => config::years(&cli.root)?,
+            };
+            let mut count = 0;
+            for year in years {
+                for file in lint::lint(&cli.root, year, *day, *fix)? {
+                    for issue in &file.issues {
+                        if file.fixed && issue.fixable() {
+                            println!("{}: fixed: {}", file.path.display(), issue);
+                        } else {
+                            println!("{}: {}", file.path.display(), issue);
+                            count += 1;
+                        }
+                    }
+                }
+            }
+            if count == 0 {
+                println!("No issues.");
+                Ok(ExitCode::SUCCESS)
+            } else {
+                println!("{} issue(s).", count);
+                Ok(ExitCode::FAILURE)
+            }
+        }
+        Command::Examples {
+            selection,
+            block,
+            write,
+            no_run,
+        } => {
+            let mut success = true;
+            for day in selection.days(&cli)? {
+                let config = DayConfig::load(&cli.root, selection.year, day)?;
+                success &= example::extract(&config, *block, *write)?;
+                if !no_run {
+                    success &= example::smoke_test(&config)?;
+                }
+            }
+            Ok(if success {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            })
+        }
+        Command::Puzzles { write } => {
+            let puzzles = puzzle::index(&cli.root)?;
+            for puzzle in &puzzles {
+                let blocks: usize = puzzle.parts.iter().map(|part| part.code_blocks.len()).sum();
+                println!(
+                    "{}/{:<3} {:<45} {} part(s), {} code block(s)",
+                    puzzle.year,
+                    puzzle.day,
+                    puzzle.heading(),
+                    puzzle.parts.len(),
+                    blocks
+                );
+            }
+            if *write {
+                let path = puzzle::write_index(&cli.root, &puzzles)?;
+                println!("Wrote {}", path.display());
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Fetch {
+            year,
+            day,
+            username,
+            client,
+        } => {
+            let client = client.client(&cli);
+            let written = client::fetch(&cli.root, &client, *year, *day, username)?;
+            for path in &written {
+                println!("Wrote {}", path.display());
+            }
+            if written.is_empty() {
+                println!("Nothing to fetch for {}/{}.", year, day);
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::ImportStars { file } => {
+            let (year, unmapped) = stars::import_file(&cli.root, &leaderboards_dir, file)?;
+            for member in &unmapped {
+                println!(
+                    "{} doesn't match an author, map them in {}.",
+                    member,
+                    stars::MEMBERS
+                );
+            }
+            println!(
+                "Show the solve times with `make show YEAR={} RANK=solved`.",
+                year
+            );
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Show { selection, rank } => {
+            let stars = stars::Stars::load(&leaderboards_dir, selection.year)?;
+            for day in selection.days(&cli)? {
+                if let Some(leaderboard) =
+                    stars::leaderboard_with_stars(&leaderboards_dir, &stars, day, *rank)?
+                {
+                    print!("{}", leaderboard.to_text());
+                }
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Submit {
+            year,
+            day,
+            username,
+            language,
+            max_wait,
+            client,
+        } => {
+            let client = client.client(&cli);
+            let history = History::open(&cli.root.join(&cli.db))?;
+            let config = DayConfig::load(&cli.root, *year, *day)?;
+            let verified = submit::submit(
+                &cli.root, &history, &client, &config, username, *language, *max_wait,
+            )?;
+            Ok(if verified {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            })
+        }
+        Command::MockServer {
+            fixtures,
+            port,
+            cooldown,
+        } => {
+            let server = mock::MockServer::start(
+                cli.root.join(fixtures),
+                *port,
+                std::time::Duration::from_secs(*cooldown),
+            )?;
+            println!("Serving {} on {}", fixtures.display(), server.url());
+            let mut logged = 0;
+            loop {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                let requests = server.requests();
+                for request in &requests[logged..] {
+                    println!("{}", request);
+                }
+                logged = requests.len();
+            }
+        }
+        Command::New {
+            year,
+            day,
+            language,
+            username,
+        } => {
+            for path in scaffold::new_solution(&cli.root, *year, *day, *language, username)? {
+                println!("Created {}", path.display());
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Validate { year, day } => {
+            let years = match year {
+                Some(year) => vec![*year],
+                None => config::years(&cli.root)?,
+            };
+            let mut count = 0;
+            for year in years {
+                for violation in validate::validate(&cli.root, year, *day)? {
+                    println!("{}", violation);
+                    count += 1;
+                }
+            }
+            if count == 0 {
+                println!("No violations.");
+                Ok(ExitCode::SUCCESS)
+            } else {
+                println!("{} violation(s).", count);
+                Ok(ExitCode::FAILURE)
+            }
+        }
+    }
+}
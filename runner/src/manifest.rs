@@ -0,0 +1,432 @@
+use anyhow::{anyhow, Context, Result};
+use semver::{Version, VersionReq};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use toml_edit::{value, ArrayOfTables, DocumentMut, Item, Table, Value};
+
+use crate::config::{self, DayConfig};
+use crate::lang::Language;
+
+/// Cargo manifest listing the crates vendored in the docker image, relative to the root.
+pub const ALLOWLIST: &str = "lang/cargo_rust_crates.toml";
+
+/// Crates in the repository which solutions may use as `path` dependencies, relative to the root.
+pub const SHARED_DIR: &str = "lib";
+
+/// `[profile.release]` settings every solution must have, as TOML values.
+const RELEASE_PROFILE: [(&str, &str); 2] = [("opt-level", "3"), ("panic", "\"abort\"")];
+
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Crates solutions may depend on.
+#[derive(Debug, Clone, Default)]
+pub struct Allowlist {
+    /// Vendored versions of each crate.
+    vendored: BTreeMap<String, Vec<Version>>,
+    /// Package names of the shared crates, by their directory in `lib`.
+    shared: BTreeMap<String, String>,
+}
+impl Allowlist {
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(ALLOWLIST);
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}.", path.display()))?;
+        let mut allowlist =
+            Self::parse(&text).with_context(|| format!("Failed to parse {}.", path.display()))?;
+        let Ok(entries) = std::fs::read_dir(root.join(SHARED_DIR)) else {
+            return Ok(allowlist);
+        };
+        for entry in entries {
+            let dir = entry?.path();
+            let manifest = dir.join("Cargo.toml");
+            let Ok(text) = std::fs::read_to_string(&manifest) else {
+                continue;
+            };
+            let doc: DocumentMut = text
+                .parse()
+                .with_context(|| format!("Failed to parse {}.", manifest.display()))?;
+            let package = doc
+                .get("package")
+                .and_then(|package| package.get("name"))
+                .and_then(Item::as_str)
+                .ok_or_else(|| anyhow!("{} has no package name.", manifest.display()))?;
+            let dir = dir.file_name().unwrap().to_string_lossy().into_owned();
+            allowlist.shared.insert(dir, package.to_owned());
+        }
+        Ok(allowlist)
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let doc: DocumentMut = text.parse()?;
+        let mut crates = BTreeMap::<_, Vec<_>>::new();
+        let deps = doc
+            .get("dependencies")
+            .and_then(Item::as_table_like)
+            .ok_or_else(|| anyhow!("Missing `[dependencies]`."))?;
+        for (key, dep) in deps.iter() {
+            let dep = Dependency::parse(key, dep);
+            let version = dep
+                .version
+                .as_deref()
+                .and_then(|v| v.strip_prefix('='))
+                .ok_or_else(|| anyhow!("`{}` must pin an exact `=` version.", key))?;
+            crates
+                .entry(dep.package)
+                .or_default()
+                .push(Version::parse(version)?);
+        }
+        Ok(Self {
+            vendored: crates,
+            shared: BTreeMap::new(),
+        })
+    }
+
+    /// Checks that a `path` dependency is one of the shared crates in `lib`.
+    fn check_path(&self, package: &str, path: &str) -> Result<(), String> {
+        let mut components = Path::new(path).components().rev();
+        let dir = components.next().and_then(|c| c.as_os_str().to_str());
+        let parent = components.next().and_then(|c| c.as_os_str().to_str());
+        match (parent, dir) {
+            (Some(SHARED_DIR), Some(dir))
+                if self.shared.get(dir).map(String::as_str) == Some(package) =>
+            {
+                Ok(())
+            }
+            _ => Err(format!(
+                "`{}` is a `path` dependency outside of {}, which isn't vendored",
+                package, SHARED_DIR
+            )),
+        }
+    }
+
+    /// Checks that a vendored version satisfies the requirement.
+    fn check(&self, package: &str, req: &str) -> Result<(), String> {
+        let versions = self.vendored.get(package).ok_or_else(|| {
+            format!(
+                "`{}` isn't vendored in the image, see {}",
+                package, ALLOWLIST
+            )
+        })?;
+        let parsed = VersionReq::parse(req)
+            .map_err(|err| format!("`{} = \"{}\"`: {}", package, req, err))?;
+        if versions.iter().any(|version| parsed.matches(version)) {
+            return Ok(());
+        }
+        let versions: Vec<_> = versions.iter().map(Version::to_string).collect();
+        Err(format!(
+            "`{} = \"{}\"` doesn't match the vendored {}",
+            package,
+            req,
+            versions.join(", ")
+        ))
+    }
+}
+
+/// The parts of a dependency entry the policy cares about.
+struct Dependency {
+    package: String,
+    version: Option<String>,
+    /// `path` or `git`, which can't be vendored.
+    source: Option<&'static str>,
+    path: Option<String>,
+}
+impl Dependency {
+    fn parse(key: &str, item: &Item) -> Self {
+        if let Some(version) = item.as_str() {
+            return Self {
+                package: key.to_owned(),
+                version: Some(version.to_owned()),
+                source: None,
+                path: None,
+            };
+        }
+        let table = item.as_table_like();
+        let get = |field: &str| {
+            table
+                .and_then(|t| t.get(field))
+                .and_then(Item::as_str)
+                .map(str::to_owned)
+        };
+        Self {
+            package: get("package").unwrap_or_else(|| key.to_owned()),
+            version: get("version"),
+            source: ["path", "git"]
+                .into_iter()
+                .find(|field| table.is_some_and(|t| t.contains_key(field))),
+            path: get("path"),
+        }
+    }
+}
+
+/// Findings for one `Cargo.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestCheck {
+    /// Policy violations that are left in the manifest.
+    pub errors: Vec<String>,
+    /// Non-standard settings that are allowed, but make timings less comparable.
+    pub warnings: Vec<String>,
+    /// Violations that were fixed in the document.
+    pub fixed: Vec<String>,
+}
+
+fn without_decor(value: &Value) -> String {
+    let mut value = value.clone();
+    value.decor_mut().clear();
+    value.to_string()
+}
+
+/// Checks a parsed `Cargo.toml` against the policy. With `fix`, the binary name and release
+/// profile are normalized in `doc`; dependencies are only ever reported.
+pub fn check(doc: &mut DocumentMut, allowlist: &Allowlist, fix: bool) -> ManifestCheck {
+    let mut result = ManifestCheck::default();
+    let mut report = |message: String, fixable: bool| {
+        if fix && fixable {
+            result.fixed.push(message);
+        } else {
+            result.errors.push(message);
+        }
+    };
+
+    // `cargo_rust.sh` runs `target/release/out`.
+    let bins = doc.get("bin").and_then(Item::as_array_of_tables);
+    let has_out = bins.is_some_and(|bins| {
+        bins.iter()
+            .any(|bin| bin.get("name").and_then(Item::as_str) == Some("out"))
+    });
+    if !has_out {
+        let bin_count = bins.map_or(0, ArrayOfTables::len);
+        report(
+            "no `[[bin]]` named `out`, which `cargo_rust.sh` runs".to_owned(),
+            bin_count <= 1,
+        );
+        if fix && bin_count == 0 {
+            let mut bin = Table::new();
+            bin["name"] = value("out");
+            bin["path"] = value("src/main.rs");
+            let mut bins = ArrayOfTables::new();
+            bins.push(bin);
+            doc["bin"] = Item::ArrayOfTables(bins);
+        } else if fix && bin_count == 1 {
+            if let Some(bin) = doc["bin"]
+                .as_array_of_tables_mut()
+                .and_then(|bins| bins.get_mut(0))
+            {
+                bin["name"] = value("out");
+            }
+        }
+    }
+
+    for (key, expected) in RELEASE_PROFILE {
+        let actual = doc
+            .get("profile")
+            .and_then(|profile| profile.get("release"))
+            .and_then(|release| release.get(key))
+            .and_then(Item::as_value)
+            .map(without_decor);
+        if actual.as_deref() == Some(expected) {
+            continue;
+        }
+        report(
+            match actual {
+                Some(actual) => format!(
+                    "`[profile.release]` has `{} = {}`, not {}",
+                    key, actual, expected
+                ),
+                None => format!("`[profile.release]` is missing `{} = {}`", key, expected),
+            },
+            true,
+        );
+        if fix {
+            if doc.get("profile").is_none() {
+                let mut profile = Table::new();
+                profile.set_implicit(true);
+                doc["profile"] = Item::Table(profile);
+            }
+            if doc["profile"].get("release").is_none() {
+                doc["profile"]["release"] = Item::Table(Table::new());
+            }
+            doc["profile"]["release"][key] = value(expected.parse::<Value>().unwrap());
+        }
+    }
+    if let Some(release) = doc
+        .get("profile")
+        .and_then(|profile| profile.get("release"))
+        .and_then(Item::as_table_like)
+    {
+        for (key, item) in release.iter() {
+            if RELEASE_PROFILE.iter().all(|(k, _)| *k != key) {
+                let setting = item.as_value().map(without_decor).unwrap_or_default();
+                result.warnings.push(format!(
+                    "non-standard `[profile.release]` setting `{} = {}`",
+                    key, setting
+                ));
+            }
+        }
+    }
+
+    for table in DEPENDENCY_TABLES {
+        let Some(deps) = doc.get(table).and_then(Item::as_table_like) else {
+            continue;
+        };
+        for (key, item) in deps.iter() {
+            let dep = Dependency::parse(key, item);
+            let checked = match (dep.source, &dep.version) {
+                (Some("path"), _) => {
+                    allowlist.check_path(&dep.package, dep.path.as_deref().unwrap_or_default())
+                }
+                (Some(source), _) => Err(format!(
+                    "`{}` is a `{}` dependency, which isn't vendored",
+                    key, source
+                )),
+                (None, None) => Err(format!("`{}` has no version", key)),
+                (None, Some(req)) => allowlist.check(&dep.package, req),
+            };
+            if let Err(message) = checked {
+                result.errors.push(format!("[{}] {}", table, message));
+            }
+        }
+    }
+    result
+}
+
+/// Checks the `Cargo.toml` of every registered Rust solution of `year` (or all years) and
+/// `day`, writing the normalized manifest back with `fix`.
+pub fn check_all(
+    root: &Path,
+    year: Option<u32>,
+    day: Option<u32>,
+    fix: bool,
+) -> Result<Vec<(PathBuf, ManifestCheck)>> {
+    let allowlist = Allowlist::load(root)?;
+    let years = match year {
+        Some(year) => vec![year],
+        None => config::years(root)?,
+    };
+    let mut results = Vec::new();
+    for year in years {
+        let days = match day {
+            Some(day) => vec![day],
+            None => config::days(root, year)?,
+        };
+        for day in days {
+            let config = DayConfig::load(root, year, day)?;
+            for solution in &config.solutions {
+                if solution.language != Language::CargoRust {
+                    continue;
+                }
+                let path = solution.path.join("Cargo.toml");
+                let Ok(text) = std::fs::read_to_string(&path) else {
+                    // Reported by `validate`.
+                    continue;
+                };
+                let mut doc: DocumentMut = text
+                    .parse()
+                    .with_context(|| format!("Failed to parse {}.", path.display()))?;
+                let result = check(&mut doc, &allowlist, fix);
+                if !result.fixed.is_empty() {
+                    std::fs::write(&path, doc.to_string())
+                        .with_context(|| format!("Failed to write {}.", path.display()))?;
+                }
+                let path = path.strip_prefix(root).unwrap_or(&path).to_owned();
+                results.push((path, result));
+            }
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ALLOWLIST_TOML: &str = r#"
+[dependencies]
+bitvec = "=1.0.1"
+bitvec_0_22 = { package = "bitvec", version = "=0.22.3" }
+itertools = "=0.10.5"
+"#;
+
+    #[test]
+    fn test_allowlist() {
+        let allowlist = Allowlist::parse(ALLOWLIST_TOML).unwrap();
+        assert_eq!(Ok(()), allowlist.check("bitvec", "0.22"));
+        assert_eq!(Ok(()), allowlist.check("bitvec", "1.0.1"));
+        assert_eq!(Ok(()), allowlist.check("itertools", "0.10.3"));
+        assert!(allowlist.check("itertools", "0.11").is_err());
+        assert!(allowlist.check("regex", "1").is_err());
+        assert!(Allowlist::parse("[dependencies]\nregex = \"1\"").is_err());
+
+        let mut allowlist = allowlist;
+        allowlist
+            .shared
+            .insert("aoc-util".to_owned(), "aoc-util".to_owned());
+        assert_eq!(
+            Ok(()),
+            allowlist.check_path("aoc-util", "../../../../../lib/aoc-util")
+        );
+        assert!(allowlist
+            .check_path("aoc-util", "../../../../../aoc-util")
+            .is_err());
+        assert!(allowlist
+            .check_path("other", "../../../../../lib/aoc-util")
+            .is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let allowlist = Allowlist::parse(ALLOWLIST_TOML).unwrap();
+        let manifest = r#"[package]
+name = "someone"
+version = "0.1.0"
+edition = "2018"
+
+[dependencies]
+itertools = "0.10.3"
+lexical-core = "0.8.2"
+local = { path = "../local" }
+
+[profile.release]
+opt-level = 2 # faster builds
+lto = true
+"#;
+        let mut doc: DocumentMut = manifest.parse().unwrap();
+        let result = check(&mut doc, &allowlist, false);
+        assert_eq!(
+            vec![
+                "no `[[bin]]` named `out`, which `cargo_rust.sh` runs",
+                "`[profile.release]` has `opt-level = 2`, not 3",
+                "`[profile.release]` is missing `panic = \"abort\"`",
+                "[dependencies] `lexical-core` isn't vendored in the image, see lang/cargo_rust_crates.toml",
+                "[dependencies] `local` is a `path` dependency outside of lib, which isn't vendored",
+            ],
+            result.errors
+        );
+        assert_eq!(
+            vec!["non-standard `[profile.release]` setting `lto = true`"],
+            result.warnings
+        );
+        assert_eq!(manifest, doc.to_string());
+
+        let result = check(&mut doc, &allowlist, true);
+        assert_eq!(3, result.fixed.len());
+        assert_eq!(2, result.errors.len());
+        let fixed = doc.to_string();
+        assert!(fixed.contains("opt-level = 3"));
+        assert!(fixed.contains("panic = \"abort\""));
+        assert!(fixed.contains("[[bin]]\nname = \"out\"\npath = \"src/main.rs\"\n"));
+
+        let mut doc: DocumentMut = fixed.parse().unwrap();
+        assert!(check(&mut doc, &allowlist, true).fixed.is_empty());
+    }
+
+    #[test]
+    fn test_template() {
+        let template =
+            include_str!("../templates/rust/Cargo.toml").replace("{{username}}", "someone");
+        let mut doc: DocumentMut = template.parse().unwrap();
+        assert_eq!(
+            ManifestCheck::default(),
+            check(&mut doc, &Allowlist::default(), false)
+        );
+    }
+}
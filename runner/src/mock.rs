@@ -0,0 +1,314 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// What adventofcode.com answers when fetching an input without a session cookie.
+const LOGIN_REQUIRED: &str =
+    "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n";
+
+/// A stand-in for adventofcode.com which serves fixtures, so the client can be used and tested
+/// offline. For each `{fixtures}/{year}/day/{day}` directory:
+/// - `GET /{year}/day/{day}` serves its `index.html`,
+/// - `GET /{year}/day/{day}/input` serves its `input`,
+/// - `POST /{year}/day/{day}/answer` checks the answer against the lines of its `answers`.
+pub struct MockServer {
+    port: u16,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// `METHOD /path` of every request.
+    requests: Vec<String>,
+    /// Parts solved of each `{year}/{day}`.
+    solved: HashMap<String, u32>,
+    /// When the next answer may be submitted.
+    wait_until: Option<Instant>,
+}
+
+/// One parsed request.
+struct Request {
+    method: String,
+    path: String,
+    session: Option<String>,
+    body: String,
+}
+impl Request {
+    /// A field of an `application/x-www-form-urlencoded` body.
+    fn form_value(&self, name: &str) -> Option<String> {
+        self.body
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| url_decode(value))
+    }
+}
+
+fn url_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => match value
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 2;
+                }
+                None => decoded.push(b'%'),
+            },
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+impl Response {
+    fn ok(content_type: &'static str, body: Vec<u8>) -> Self {
+        Self {
+            status: 200,
+            content_type,
+            body,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain",
+            body: message.as_bytes().to_vec(),
+        }
+    }
+
+    /// A page whose `<article>` holds `message`, like answer responses.
+    fn article(message: &str) -> Self {
+        let page = format!(
+            "<!DOCTYPE html>\n<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>\n",
+            message
+        );
+        Self::ok("text/html", page.into_bytes())
+    }
+
+    fn not_found() -> Self {
+        Self::error(404, "404 Not Found\n")
+    }
+}
+
+impl MockServer {
+    /// Starts serving on `127.0.0.1:{port}` in a background thread, on a free port if 0. After
+    /// a wrong answer, answers are rate limited for `cooldown`.
+    pub fn start(fixtures: PathBuf, port: u16, cooldown: Duration) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .with_context(|| format!("Failed to listen on port {}.", port))?;
+        let port = listener.local_addr()?.port();
+        let state = Arc::new(Mutex::new(State::default()));
+        let shared = Arc::clone(&state);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Err(err) = serve(stream, &fixtures, cooldown, &shared) {
+                    eprintln!("Mock server error: {:#}", err);
+                }
+            }
+        });
+        Ok(Self { port, state })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    /// `METHOD /path` of every request served so far.
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+fn serve(
+    stream: TcpStream,
+    fixtures: &Path,
+    cooldown: Duration,
+    state: &Mutex<State>,
+) -> Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let request = read_request(&mut reader)?;
+    let response = {
+        let mut state = state.lock().unwrap();
+        state
+            .requests
+            .push(format!("{} {}", request.method, request.path));
+        respond(&request, fixtures, cooldown, &mut state)
+    };
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        _ => "Error",
+    };
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason,
+        response.content_type,
+        response.body.len()
+    )?;
+    stream.write_all(&response.body)?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn read_request(reader: &mut impl BufRead) -> Result<Request> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut words = line.split_whitespace();
+    let method = words.next().unwrap_or_default().to_owned();
+    let path = words.next().unwrap_or_default().to_owned();
+    let mut session = None;
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "cookie" => {
+                session = value
+                    .split(';')
+                    .filter_map(|cookie| cookie.trim().strip_prefix("session="))
+                    .next()
+                    .map(str::to_owned);
+            }
+            "content-length" => content_length = value.trim().parse()?,
+            _ => {}
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Request {
+        method,
+        path,
+        session,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+fn respond(request: &Request, fixtures: &Path, cooldown: Duration, state: &mut State) -> Response {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    let (year, day, rest) = match segments.as_slice() {
+        [year, "day", day, rest @ ..]
+            if year.parse::<u32>().is_ok() && day.parse::<u32>().is_ok() =>
+        {
+            (*year, *day, rest)
+        }
+        _ => return Response::not_found(),
+    };
+    let dir = fixtures.join(year).join("day").join(day);
+    let logged_in = request.session.as_deref().is_some_and(|s| !s.is_empty());
+    let (file, content_type) = match (request.method.as_str(), rest) {
+        ("GET", []) => ("index.html", "text/html"),
+        ("GET", ["input"]) if !logged_in => return Response::error(400, LOGIN_REQUIRED),
+        ("GET", ["input"]) => ("input", "text/plain"),
+        ("POST", ["answer"]) if !logged_in => return Response::error(400, "Please log in.\n"),
+        ("POST", ["answer"]) => {
+            let Ok(answers) = std::fs::read_to_string(dir.join("answers")) else {
+                return Response::not_found();
+            };
+            let key = format!("{}/{}", year, day);
+            return answer(request, &key, &answers, cooldown, state);
+        }
+        _ => return Response::not_found(),
+    };
+    match std::fs::read(dir.join(file)) {
+        Ok(body) => Response::ok(content_type, body),
+        Err(_) => Response::not_found(),
+    }
+}
+
+/// Checks a submitted answer like adventofcode.com, with the same wording.
+fn answer(
+    request: &Request,
+    key: &str,
+    answers: &str,
+    cooldown: Duration,
+    state: &mut State,
+) -> Response {
+    let now = Instant::now();
+    if let Some(left) = state
+        .wait_until
+        .and_then(|until| until.checked_duration_since(now))
+    {
+        let secs = left.as_secs().max(1);
+        let left = match secs / 60 {
+            0 => format!("{}s", secs),
+            minutes => format!("{}m {}s", minutes, secs % 60),
+        };
+        return Response::article(&format!(
+            "You gave an answer too recently; you have to wait after submitting an answer \
+             before trying again.  You have {} left to wait.",
+            left
+        ));
+    }
+    let level: u32 = request
+        .form_value("level")
+        .and_then(|level| level.parse().ok())
+        .unwrap_or_default();
+    let given = request.form_value("answer").unwrap_or_default();
+    let solved = state.solved.entry(key.to_owned()).or_default();
+    let Some(expected) = answers.lines().nth(level.wrapping_sub(1) as usize) else {
+        return Response::not_found();
+    };
+    if level != *solved + 1 {
+        return Response::article(
+            "You don't seem to be solving the right level.  Did you already complete it?",
+        );
+    }
+    if given.trim() == expected.trim() {
+        *solved = level;
+        return Response::article(
+            "That's the right answer!  You are <span class=\"day-success\">one gold star</span> \
+             closer to saving Christmas.",
+        );
+    }
+    let hint = match (
+        given.trim().parse::<i128>(),
+        expected.trim().parse::<i128>(),
+    ) {
+        (Ok(given), Ok(expected)) if given > expected => "; your answer is too high",
+        (Ok(given), Ok(expected)) if given < expected => "; your answer is too low",
+        _ => "",
+    };
+    let wait = match cooldown.as_secs() {
+        0 => String::new(),
+        60 => " Please wait one minute before trying again.".to_owned(),
+        secs if secs % 60 == 0 => {
+            format!(" Please wait {} minutes before trying again.", secs / 60)
+        }
+        secs => format!(" Please wait {} seconds before trying again.", secs),
+    };
+    if !cooldown.is_zero() {
+        state.wait_until = Some(now + cooldown);
+    }
+    Response::article(&format!(
+        "That's not the right answer{}.  If you're stuck, make sure you're using the full input \
+         data.{}",
+        hint, wait
+    ))
+}
@@ -0,0 +1,120 @@
+use crate::leaderboard::{Entry, Leaderboard};
+
+#[derive(Debug, Clone, Copy)]
+pub struct PerfOptions {
+    /// Allowed relative slowdown, e.g. `0.5` allows 50% slower.
+    pub threshold: f64,
+    /// Number of standard deviations of measurement noise to tolerate on top of `threshold`.
+    pub sigma: f64,
+    /// Slowdowns smaller than this many milliseconds are never flagged.
+    pub min_delta_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub day: u32,
+    pub language: String,
+    pub author: String,
+    pub baseline_ms: u64,
+    pub fresh_ms: u64,
+    /// Per-part times of both, to tell which part got slower. Empty where unknown.
+    pub baseline_parts_ms: Vec<u64>,
+    pub fresh_parts_ms: Vec<u64>,
+    /// The largest time that would not have been flagged.
+    pub limit_ms: f64,
+}
+impl Regression {
+    pub fn slowdown(&self) -> f64 {
+        self.fresh_ms as f64 / self.baseline_ms.max(1) as f64
+    }
+}
+
+/// Compares passing entries of `fresh` against `baseline`. An entry regresses if its total exceeds
+/// `baseline * (1 + threshold) + sigma * noise`, where noise combines the standard deviation of
+/// both measurements. Text leaderboards carry no samples, so only the fresh variance counts there.
+pub fn check(baseline: &Leaderboard, fresh: &Leaderboard, opts: &PerfOptions) -> Vec<Regression> {
+    fresh
+        .entries
+        .iter()
+        .filter(|entry| entry.passed())
+        .filter_map(|fresh_entry| {
+            let base_entry = baseline
+                .get(fresh_entry.key())
+                .filter(|entry| entry.passed())?;
+            check_entry(base_entry, fresh_entry, opts).map(|limit_ms| Regression {
+                day: fresh.day,
+                language: fresh_entry.language.clone(),
+                author: fresh_entry.author.clone(),
+                baseline_ms: base_entry.total_ms,
+                fresh_ms: fresh_entry.total_ms,
+                baseline_parts_ms: base_entry.parts_ms.clone(),
+                fresh_parts_ms: fresh_entry.parts_ms.clone(),
+                limit_ms,
+            })
+        })
+        .collect()
+}
+
+fn check_entry(base: &Entry, fresh: &Entry, opts: &PerfOptions) -> Option<f64> {
+    let noise = (base.stddev_ms().powi(2) + fresh.stddev_ms().powi(2)).sqrt();
+    let limit = base.total_ms as f64 * (1.0 + opts.threshold) + opts.sigma * noise;
+    let limit = limit.max((base.total_ms + opts.min_delta_ms) as f64);
+    (fresh.total_ms as f64 > limit).then_some(limit)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::leaderboard::Status;
+
+    fn entry(total_ms: u64, samples_ms: Vec<u64>) -> Entry {
+        Entry {
+            language: "Rust".to_owned(),
+            author: "mingweisamuel".to_owned(),
+            compile_ms: 0,
+            total_ms,
+            average_ms: 0,
+            status: Status::Passed,
+            samples_ms,
+            parts_ms: Vec::new(),
+            unverified: Vec::new(),
+            toolchain: None,
+            solved_s: Vec::new(),
+        }
+    }
+
+    const OPTS: PerfOptions = PerfOptions {
+        threshold: 0.5,
+        sigma: 2.0,
+        min_delta_ms: 5,
+    };
+
+    #[test]
+    fn test_threshold() {
+        assert_eq!(
+            None,
+            check_entry(&entry(100, vec![]), &entry(150, vec![]), &OPTS)
+        );
+        assert_eq!(
+            Some(150.0),
+            check_entry(&entry(100, vec![]), &entry(151, vec![]), &OPTS)
+        );
+    }
+
+    #[test]
+    fn test_variance() {
+        let noisy = entry(160, vec![100, 220, 160]);
+        assert_eq!(None, check_entry(&entry(100, vec![]), &noisy, &OPTS));
+        let steady = entry(160, vec![159, 161, 160]);
+        assert!(check_entry(&entry(100, vec![]), &steady, &OPTS).is_some());
+    }
+
+    #[test]
+    fn test_min_delta() {
+        assert_eq!(
+            None,
+            check_entry(&entry(4, vec![]), &entry(9, vec![]), &OPTS)
+        );
+        assert!(check_entry(&entry(4, vec![]), &entry(10, vec![]), &OPTS).is_some());
+    }
+}
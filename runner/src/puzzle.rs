@@ -0,0 +1,303 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+/// Index of every puzzle, relative to the root.
+pub const INDEX: &str = "puzzles.json";
+
+/// One part of a puzzle's text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Part {
+    /// Markdown between this part's heading and the next.
+    pub text: String,
+    pub code_blocks: Vec<CodeBlock>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeBlock {
+    /// 1-based line of the README the contents start on.
+    pub line: usize,
+    /// Contents, with a trailing newline.
+    pub text: String,
+    /// The last line of prose before the block, e.g. `For example:`.
+    pub context: String,
+}
+
+/// Metadata of a `{year}/{day}/README.md`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Puzzle {
+    pub year: u32,
+    pub day: u32,
+    pub title: String,
+    pub parts: Vec<Part>,
+}
+impl Puzzle {
+    /// Parses the `README.md` in `day_dir`, or returns `None` if there is none (or it's empty,
+    /// like 2021/24's).
+    pub fn load(day_dir: &Path, year: u32, day: u32) -> Result<Option<Self>> {
+        let path = day_dir.join("README.md");
+        let Ok(markdown) = std::fs::read_to_string(&path) else {
+            return Ok(None);
+        };
+        if markdown.trim().is_empty() {
+            return Ok(None);
+        }
+        Self::parse(year, day, &markdown)
+            .map(Some)
+            .with_context(|| format!("Failed to parse {}.", path.display()))
+    }
+
+    /// `Day 1: Sonar Sweep`.
+    pub fn heading(&self) -> String {
+        format!("Day {}: {}", self.day, self.title)
+    }
+
+    /// Parses both README styles in the repository: `## --- Day 1: Sonar Sweep ---` with fenced
+    /// code blocks, and `\--- Day 13: Transparent Origami ---` underlined with dashes with
+    /// indented code blocks. A line that is only an inline code span (2021/7's example) is a
+    /// code block too.
+    pub fn parse(year: u32, day: u32, markdown: &str) -> Result<Self> {
+        let lines: Vec<&str> = markdown.lines().collect();
+        let mut title = None;
+        let mut parts: Vec<Part> = Vec::new();
+        let mut code: Option<CodeBlock> = None;
+        let mut fenced = false;
+        let mut context = "";
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            i += 1;
+
+            if fenced {
+                let block = code.as_mut().unwrap();
+                if line.trim_end() == "```" {
+                    fenced = false;
+                    parts.last_mut().unwrap().code_blocks.extend(code.take());
+                } else {
+                    block.text.push_str(line);
+                    block.text.push('\n');
+                }
+                continue;
+            }
+            if let Some(block) = &mut code {
+                // Indented blocks continue through lines of only whitespace.
+                if let Some(rest) = line
+                    .strip_prefix("    ")
+                    .or_else(|| line.strip_prefix('\t'))
+                {
+                    block.text.push_str(rest);
+                    block.text.push('\n');
+                    continue;
+                }
+                if line.trim().is_empty() {
+                    block.text.push('\n');
+                    continue;
+                }
+                let mut block = code.take().unwrap();
+                block.text.truncate(block.text.trim_end().len());
+                block.text.push('\n');
+                parts.last_mut().unwrap().code_blocks.push(block);
+            }
+
+            let heading = if let Some(rest) = line.trim_end().strip_prefix("## --- ") {
+                Some(rest)
+            } else if let Some(rest) = line.trim_end().strip_prefix("\\--- ") {
+                // Setext style, underlined on the next line.
+                if lines
+                    .get(i)
+                    .is_some_and(|next| !next.is_empty() && next.chars().all(|c| c == '-'))
+                {
+                    i += 1;
+                }
+                Some(rest)
+            } else {
+                None
+            };
+            if let Some(heading) = heading {
+                let heading = heading.trim_end_matches('-').trim();
+                if let Some(rest) = heading.strip_prefix("Day ") {
+                    let (num, name) = rest
+                        .split_once(':')
+                        .ok_or_else(|| anyhow!("Bad heading: {}", line))?;
+                    if num.trim() != day.to_string() {
+                        return Err(anyhow!("Heading is for day {}: {}", num, line));
+                    }
+                    title = Some(name.trim().to_owned());
+                }
+                parts.push(Part {
+                    text: String::new(),
+                    code_blocks: Vec::new(),
+                });
+                context = "";
+                continue;
+            }
+            let Some(part) = parts.last_mut() else {
+                continue;
+            };
+            part.text.push_str(line);
+            part.text.push('\n');
+
+            if line.trim_end() == "```" {
+                fenced = true;
+                code = Some(CodeBlock {
+                    line: i + 1,
+                    text: String::new(),
+                    context: context.to_owned(),
+                });
+            } else if let Some(rest) = line
+                .strip_prefix("    ")
+                .or_else(|| line.strip_prefix('\t'))
+            {
+                if !rest.trim().is_empty() && !is_list_item(rest) {
+                    code = Some(CodeBlock {
+                        line: i,
+                        text: format!("{}\n", rest),
+                        context: context.to_owned(),
+                    });
+                }
+            } else {
+                let trimmed = line.trim();
+                let inline = trimmed
+                    .strip_prefix('`')
+                    .and_then(|rest| rest.strip_suffix('`'))
+                    .filter(|inner| !inner.is_empty() && !inner.contains('`'));
+                if let Some(inner) = inline {
+                    part.code_blocks.push(CodeBlock {
+                        line: i,
+                        text: format!("{}\n", inner),
+                        context: context.to_owned(),
+                    });
+                } else if !trimmed.is_empty() {
+                    context = trimmed;
+                }
+            }
+        }
+        if let Some(mut block) = code {
+            if fenced {
+                return Err(anyhow!("Unclosed code block on line {}.", block.line));
+            }
+            block.text.truncate(block.text.trim_end().len());
+            block.text.push('\n');
+            parts.last_mut().unwrap().code_blocks.push(block);
+        }
+        for part in &mut parts {
+            part.text = part.text.trim().to_owned();
+        }
+        Ok(Self {
+            year,
+            day,
+            title: title.ok_or_else(|| anyhow!("Missing `--- Day {}: ... ---` heading.", day))?,
+            parts,
+        })
+    }
+}
+
+/// Nested list items are indented too, but aren't code.
+fn is_list_item(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("- ") || line.starts_with("* ")
+}
+
+/// Parses the README of every day which has one.
+pub fn index(root: &Path) -> Result<Vec<Puzzle>> {
+    let mut puzzles = Vec::new();
+    for year in config::years(root)? {
+        let year_dir = root.join(year.to_string());
+        let mut days = std::fs::read_dir(&year_dir)
+            .with_context(|| format!("Failed to read {}.", year_dir.display()))?
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+            .collect::<Vec<u32>>();
+        days.sort_unstable();
+        for day in days {
+            puzzles.extend(Puzzle::load(&year_dir.join(day.to_string()), year, day)?);
+        }
+    }
+    Ok(puzzles)
+}
+
+/// Writes the index to `puzzles.json`.
+pub fn write_index(root: &Path, puzzles: &[Puzzle]) -> Result<PathBuf> {
+    let path = root.join(INDEX);
+    let mut json = serde_json::to_string_pretty(puzzles)?;
+    json.push('\n');
+    std::fs::write(&path, json).with_context(|| format!("Failed to write {}.", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_fenced() {
+        let markdown = "## --- Day 1: Sonar Sweep ---
+For example, suppose you had the following report:
+```
+199
+200
+```
+This report indicates...
+
+`16,1,2`
+## --- Part Two ---
+```
+## not a heading
+```
+";
+        let puzzle = Puzzle::parse(2021, 1, markdown).unwrap();
+        assert_eq!("Day 1: Sonar Sweep", puzzle.heading());
+        assert_eq!(2, puzzle.parts.len());
+        assert_eq!(
+            vec![
+                CodeBlock {
+                    line: 4,
+                    text: "199\n200\n".to_owned(),
+                    context: "For example, suppose you had the following report:".to_owned(),
+                },
+                CodeBlock {
+                    line: 9,
+                    text: "16,1,2\n".to_owned(),
+                    context: "This report indicates...".to_owned(),
+                },
+            ],
+            puzzle.parts[0].code_blocks
+        );
+        assert!(puzzle.parts[0].text.starts_with("For example"));
+        assert_eq!("## not a heading\n", puzzle.parts[1].code_blocks[0].text);
+    }
+
+    #[test]
+    fn test_parse_setext() {
+        let markdown = "\\--- Day 14: Extended Polymerization ---
+----------------------------------------
+
+For example:
+
+    NNCB
+
+    CH -> B
+
+After step 1:
+    - nested list
+\\--- Part Two ---
+-----------------
+
+Done.
+";
+        let puzzle = Puzzle::parse(2021, 14, markdown).unwrap();
+        assert_eq!("Extended Polymerization", puzzle.title);
+        assert_eq!(2, puzzle.parts.len());
+        assert_eq!(
+            vec![CodeBlock {
+                line: 6,
+                text: "NNCB\n\nCH -> B\n".to_owned(),
+                context: "For example:".to_owned(),
+            }],
+            puzzle.parts[0].code_blocks
+        );
+        assert_eq!("Done.", puzzle.parts[1].text);
+        assert!(Puzzle::parse(2021, 15, markdown).is_err());
+    }
+}
@@ -0,0 +1,255 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::config::DayConfig;
+use crate::lang::Language;
+
+/// A template file, relative to the solution's `{username}` directory. `{{username}}`, `{{year}}`
+/// and `{{day}}` are replaced in both the path and the contents.
+type TemplateFile = (&'static str, &'static str);
+
+macro_rules! template {
+    ($lang:literal, $path:literal) => {
+        (
+            $path,
+            include_str!(concat!("../templates/", $lang, "/", $path)),
+        )
+    };
+}
+
+struct Template {
+    files: &'static [TemplateFile],
+    /// Solution path as registered in `test.sh`, relative to the `{username}` directory. Empty
+    /// for languages whose solution is the directory itself.
+    entry: &'static str,
+}
+
+fn template(lang: Language) -> Template {
+    let (files, entry): (&'static [TemplateFile], _) = match lang {
+        Language::CargoRust => (
+            &[
+                template!("rust", ".cargo/config"),
+                template!("rust", ".gitignore"),
+                template!("rust", "Cargo.toml"),
+                template!("rust", "src/main.rs"),
+            ],
+            "",
+        ),
+        Language::CmakeCpp => (
+            &[
+                template!("cmake_cpp", ".gitignore"),
+                template!("cmake_cpp", "CMakeLists.txt"),
+                template!("cmake_cpp", "main.cpp"),
+            ],
+            "",
+        ),
+        Language::DotnetCSharp => (
+            &[
+                template!("dotnet_c_sharp", ".gitignore"),
+                template!("dotnet_c_sharp", "Program.cs"),
+                template!("dotnet_c_sharp", "{{username}}.csproj"),
+            ],
+            "",
+        ),
+        Language::Golang => (
+            &[
+                template!("golang", ".gitignore"),
+                template!("golang", "go.mod"),
+                template!("golang", "main.go"),
+            ],
+            "",
+        ),
+        Language::Golfscript => (&[template!("golfscript", "main.gs")], "main.gs"),
+        Language::Java => (&[template!("java", "Main.java")], "Main"),
+        Language::MonoCSharp => (&[template!("mono_c_sharp", "Program.cs")], "Program.cs"),
+        Language::Node => (&[template!("node", "main.mjs")], "main.mjs"),
+        Language::Osabie => (&[template!("05ab1e", "main.abe")], "main.abe"),
+        Language::Perl => (&[template!("perl", "main.pl")], "main.pl"),
+        Language::Python3 => (&[template!("python", "main.py")], "main.py"),
+    };
+    Template { files, entry }
+}
+
+const TEST_SH_HEADER: &str = r#"#!/usr/bin/env bash
+set -euo pipefail
+
+D=$(dirname $(realpath $0))
+
+echo "-- Day {{day}} --"
+printf "%-10s %-15s %-10s %-10s %-10s %-10s \n" "language" "author" "compile" "total" "average" "status"
+"#;
+const TEST_SH_FOOTER: &str = "\necho \"\"\n";
+
+fn fill(template: &str, year: u32, day: u32, username: &str) -> String {
+    template
+        .replace("{{username}}", username)
+        .replace("{{year}}", &year.to_string())
+        .replace("{{day}}", &day.to_string())
+}
+
+/// Inserts the `lang/*.sh` line before the trailing `echo ""`, or at the end if there is none.
+fn register(script: &str, line: &str) -> String {
+    let mut lines: Vec<&str> = script.lines().collect();
+    let at = lines
+        .iter()
+        .rposition(|l| l.trim() == "echo \"\"")
+        .map(|i| {
+            // Keep the blank line separating the solutions from the `echo ""`.
+            if i > 0 && lines[i - 1].trim().is_empty() {
+                i - 1
+            } else {
+                i
+            }
+        })
+        .unwrap_or(lines.len());
+    lines.insert(at, line);
+    let mut script = lines.join("\n");
+    script.push('\n');
+    script
+}
+
+/// Creates `{year}/{day}/solutions/{language}/{username}` from the language's template, empty
+/// `io/{username}.{input,output}` placeholders and registers the solution in `test.sh`. Returns
+/// the created paths.
+pub fn new_solution(
+    root: &Path,
+    year: u32,
+    day: u32,
+    lang: Language,
+    username: &str,
+) -> Result<Vec<PathBuf>> {
+    if username.is_empty()
+        || !username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        bail!("Invalid username `{}`.", username);
+    }
+    let day_dir = root.join(year.to_string()).join(day.to_string());
+    let user_dir = day_dir
+        .join("solutions")
+        .join(lang.dir_name())
+        .join(username);
+    if user_dir.exists() {
+        bail!("{} already exists.", user_dir.display());
+    }
+    let test_sh = day_dir.join("test.sh");
+    let script = if test_sh.is_file() {
+        let config = DayConfig::load(root, year, day)?;
+        if let Some(existing) = config
+            .solutions
+            .iter()
+            .find(|s| s.language == lang && s.author() == username)
+        {
+            bail!(
+                "{} is already registered in {}.",
+                existing.path.display(),
+                test_sh.display()
+            );
+        }
+        std::fs::read_to_string(&test_sh)?
+    } else {
+        format!(
+            "{}{}",
+            fill(TEST_SH_HEADER, year, day, username),
+            TEST_SH_FOOTER
+        )
+    };
+
+    let mut created = Vec::new();
+    let template = template(lang);
+    for (path, contents) in template.files {
+        let path = user_dir.join(fill(path, year, day, username));
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(&path, fill(contents, year, day, username))
+            .with_context(|| format!("Failed to write {}.", path.display()))?;
+        created.push(path);
+    }
+
+    let io_dir = day_dir.join("io");
+    std::fs::create_dir_all(&io_dir)?;
+    for ext in ["input", "output"] {
+        let path = io_dir.join(format!("{}.{}", username, ext));
+        // The author's solution in another language may have brought the io already.
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(_) => created.push(path),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to write {}.", path.display()))
+            }
+        }
+    }
+
+    let mut solution = format!("$D/solutions/{}/{}", lang.dir_name(), username);
+    if !template.entry.is_empty() {
+        solution = format!("{}/{}", solution, template.entry);
+    }
+    let line = format!(
+        "{:<40}{:<48}\"$D/io/*\"",
+        format!("$D/../../lang/{}.sh", lang.script()),
+        format!("\"{}\"", solution)
+    );
+    let is_new = !test_sh.exists();
+    std::fs::write(&test_sh, register(&script, &line))
+        .with_context(|| format!("Failed to write {}.", test_sh.display()))?;
+    if is_new {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&test_sh, std::fs::Permissions::from_mode(0o755))?;
+        }
+        created.push(test_sh);
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn test_register() {
+        let script = "echo \"-- Day 3 --\"\n$D/../../lang/node.sh \"$D/solutions/node/stelar7/main.mjs\" \"$D/io/*\"\n\necho \"\"\n";
+        assert_eq!(
+            "echo \"-- Day 3 --\"\n$D/../../lang/node.sh \"$D/solutions/node/stelar7/main.mjs\" \"$D/io/*\"\nNEW\n\necho \"\"\n",
+            register(script, "NEW")
+        );
+        assert_eq!("a\nNEW\n", register("a\n", "NEW"));
+    }
+
+    #[test]
+    fn test_new_solution() {
+        let root = TempDir::new("scaffold");
+        for lang in Language::ALL {
+            new_solution(&root, 2022, 5, lang, "someone").unwrap();
+        }
+        assert!(new_solution(&root, 2022, 5, Language::CargoRust, "someone").is_err());
+        let config = DayConfig::load(&root, 2022, 5).unwrap();
+        let missing: Vec<_> = config
+            .solutions
+            .iter()
+            .filter_map(|solution| solution.language.missing_entry_file(&solution.path))
+            .collect();
+        let cargo_toml =
+            std::fs::read_to_string(root.join("2022/5/solutions/rust/someone/Cargo.toml"));
+        let cargo_config =
+            std::fs::read_to_string(root.join("2022/5/solutions/rust/someone/.cargo/config"));
+        let io_input = std::fs::metadata(root.join("2022/5/io/someone.input"));
+
+        assert_eq!(Language::ALL.len(), config.solutions.len());
+        assert!(config
+            .solutions
+            .iter()
+            .all(|solution| solution.author() == "someone"));
+        assert_eq!(Vec::<PathBuf>::new(), missing);
+        assert!(cargo_toml.unwrap().contains("name = \"someone\""));
+        // Built like every other Rust solution.
+        assert!(cargo_config.unwrap().contains("target-cpu=native"));
+        assert_eq!(0, io_input.unwrap().len());
+    }
+}
@@ -0,0 +1,346 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use toml_edit::DocumentMut;
+
+use crate::config::{self, DayConfig};
+use crate::leaderboard::{Leaderboard, Rank};
+
+/// Private leaderboard members whose username differs from their name, relative to the root.
+pub const MEMBERS: &str = "members.toml";
+
+/// Star timestamps of a year, per day and username, relative to the leaderboards directory.
+fn stars_path(leaderboards_dir: &Path, year: u32) -> PathBuf {
+    leaderboards_dir.join(year.to_string()).join("stars.json")
+}
+
+/// Unix seconds when a puzzle unlocks: midnight EST (UTC-5) on December `day`.
+pub fn unlock_timestamp(year: u32, day: u32) -> i64 {
+    // Days since 1970-01-01 of the civil date, from Howard Hinnant's `days_from_civil`, with
+    // the year starting in March.
+    let (y, m, d) = (year as i64, 12, day as i64);
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    days * 86400 + 5 * 3600
+}
+
+/// Maps private leaderboard members, by id or name, to their `{username}` directories.
+#[derive(Debug, Clone, Default)]
+pub struct Members(BTreeMap<String, String>);
+impl Members {
+    /// Loads `members.toml`, which is optional.
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(MEMBERS);
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            return Ok(Self::default());
+        };
+        Self::parse(&text).with_context(|| format!("Failed to parse {}.", path.display()))
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let doc: DocumentMut = text.parse()?;
+        doc.iter()
+            .map(|(key, item)| {
+                let username = item
+                    .as_str()
+                    .ok_or_else(|| anyhow!("`{}` must be a username.", key))?;
+                Ok((key.to_owned(), username.to_owned()))
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    /// The username of a member: mapped by id, then by name, then the name itself if it's one
+    /// of the `usernames` ignoring case.
+    fn username(
+        &self,
+        id: u64,
+        name: Option<&str>,
+        usernames: &BTreeSet<String>,
+    ) -> Option<String> {
+        let name = name.unwrap_or_default();
+        self.0
+            .get(&id.to_string())
+            .or_else(|| self.0.get(name))
+            .cloned()
+            .or_else(|| {
+                usernames
+                    .iter()
+                    .find(|username| username.eq_ignore_ascii_case(name))
+                    .cloned()
+            })
+    }
+}
+
+/// The JSON export of a private leaderboard, `/{year}/leaderboard/private/view/{id}.json`.
+#[derive(Debug, Deserialize)]
+pub struct PrivateLeaderboard {
+    event: String,
+    members: BTreeMap<String, Member>,
+}
+impl PrivateLeaderboard {
+    pub fn parse(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn year(&self) -> Result<u32> {
+        self.event
+            .parse()
+            .with_context(|| format!("Bad event `{}`.", self.event))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Member {
+    id: u64,
+    /// `null` for anonymous users.
+    name: Option<String>,
+    /// Day, then part.
+    completion_day_level: BTreeMap<String, BTreeMap<String, Star>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Star {
+    get_star_ts: i64,
+}
+
+/// Star timestamps of one year: day, then username, then the Unix timestamp of each part.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stars {
+    pub year: u32,
+    pub days: BTreeMap<u32, BTreeMap<String, Vec<i64>>>,
+}
+impl Stars {
+    /// Loads `leaderboards/{year}/stars.json`, empty if nothing was imported.
+    pub fn load(leaderboards_dir: &Path, year: u32) -> Result<Self> {
+        let path = stars_path(leaderboards_dir, year);
+        let Ok(json) = std::fs::read_to_string(&path) else {
+            return Ok(Self {
+                year,
+                days: BTreeMap::new(),
+            });
+        };
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}.", path.display()))
+    }
+
+    pub fn save(&self, leaderboards_dir: &Path) -> Result<PathBuf> {
+        let path = stars_path(leaderboards_dir, self.year);
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        std::fs::write(&path, json)
+            .with_context(|| format!("Failed to write {}.", path.display()))?;
+        Ok(path)
+    }
+
+    /// The stars of a private leaderboard export, keeping only the members that map to one of
+    /// the `usernames`. Also returns the names of the members that don't.
+    pub fn import(
+        export: &PrivateLeaderboard,
+        members: &Members,
+        usernames: &BTreeSet<String>,
+    ) -> Result<(Self, Vec<String>)> {
+        let mut stars = Self {
+            year: export.year()?,
+            days: BTreeMap::new(),
+        };
+        let mut unmapped = Vec::new();
+        for member in export.members.values() {
+            let name = member.name.as_deref();
+            let username = members
+                .username(member.id, name, usernames)
+                .filter(|username| usernames.contains(username));
+            let Some(username) = username else {
+                if !member.completion_day_level.is_empty() {
+                    unmapped.push(match name {
+                        Some(name) => format!("{} ({})", name, member.id),
+                        None => format!("anonymous user #{}", member.id),
+                    });
+                }
+                continue;
+            };
+            for (day, parts) in &member.completion_day_level {
+                let day = day.parse().with_context(|| format!("Bad day `{}`.", day))?;
+                let mut parts: Vec<(u32, i64)> = parts
+                    .iter()
+                    .map(|(part, star)| Ok((part.parse()?, star.get_star_ts)))
+                    .collect::<Result<_>>()?;
+                parts.sort_unstable();
+                stars.days.entry(day).or_default().insert(
+                    username.clone(),
+                    parts.into_iter().map(|(_, ts)| ts).collect(),
+                );
+            }
+        }
+        Ok((stars, unmapped))
+    }
+
+    /// Adds newer imports, replacing the stars of the same day and username.
+    pub fn extend(&mut self, other: Self) {
+        for (day, users) in other.days {
+            self.days.entry(day).or_default().extend(users);
+        }
+    }
+
+    /// Fills in `solved_s` of the entries whose author has stars that day.
+    pub fn merge_into(&self, leaderboard: &mut Leaderboard) {
+        let Some(users) = self.days.get(&leaderboard.day) else {
+            return;
+        };
+        let unlock = unlock_timestamp(self.year, leaderboard.day);
+        for entry in &mut leaderboard.entries {
+            if let Some(timestamps) = users.get(&entry.author) {
+                entry.solved_s = timestamps
+                    .iter()
+                    .map(|&ts| (ts - unlock).max(0) as u64)
+                    .collect();
+            }
+        }
+    }
+}
+
+/// Authors of every solution of `year`.
+fn usernames(root: &Path, year: u32) -> Result<BTreeSet<String>> {
+    let mut usernames = BTreeSet::new();
+    for day in config::days(root, year)? {
+        let config = DayConfig::load(root, year, day)?;
+        usernames.extend(config.solutions.iter().map(|solution| solution.author()));
+    }
+    Ok(usernames)
+}
+
+/// Imports a private leaderboard export into `leaderboards/{year}/stars.json`, which `make test`
+/// doesn't overwrite, unlike the leaderboards themselves: the solve times are merged in when a
+/// leaderboard is shown or run instead. Returns the year and the members that couldn't be
+/// matched to an author.
+pub fn import_file(
+    root: &Path,
+    leaderboards_dir: &Path,
+    file: &Path,
+) -> Result<(u32, Vec<String>)> {
+    let json = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}.", file.display()))?;
+    let export = PrivateLeaderboard::parse(&json)
+        .with_context(|| format!("Failed to parse {}.", file.display()))?;
+    let year = export.year()?;
+    let members = Members::load(root)?;
+    let (imported, unmapped) = Stars::import(&export, &members, &usernames(root, year)?)?;
+    let mut stars = Stars::load(leaderboards_dir, year)?;
+    stars.extend(imported);
+    println!("Wrote {}", stars.save(leaderboards_dir)?.display());
+    Ok((year, unmapped))
+}
+
+/// The committed leaderboard of a day with the imported solve times, ranked by `rank`.
+pub fn leaderboard_with_stars(
+    leaderboards_dir: &Path,
+    stars: &Stars,
+    day: u32,
+    rank: Rank,
+) -> Result<Option<Leaderboard>> {
+    let Some(mut leaderboard) = Leaderboard::load(leaderboards_dir, stars.year, day)? else {
+        return Ok(None);
+    };
+    stars.merge_into(&mut leaderboard);
+    leaderboard.sort_by(rank);
+    Ok(Some(leaderboard))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    const EXPORT: &str = r#"{
+  "owner_id": 1,
+  "event": "2022",
+  "members": {
+    "1": {
+      "id": 1, "name": "Stelar7", "stars": 3, "local_score": 10, "global_score": 0,
+      "last_star_ts": 1669957200,
+      "completion_day_level": {
+        "1": {
+          "2": {"get_star_ts": 1669871400, "star_index": 12},
+          "1": {"get_star_ts": 1669871100, "star_index": 10}
+        },
+        "2": {"1": {"get_star_ts": 1669957200, "star_index": 20}}
+      }
+    },
+    "2": {
+      "id": 2, "name": "Mingwei Samuel", "stars": 1, "local_score": 5, "global_score": 0,
+      "last_star_ts": 1669870900,
+      "completion_day_level": {"1": {"1": {"get_star_ts": 1669870900, "star_index": 5}}}
+    },
+    "3": {
+      "id": 3, "name": null, "stars": 1, "local_score": 1, "global_score": 0,
+      "last_star_ts": 1669880000,
+      "completion_day_level": {"1": {"1": {"get_star_ts": 1669880000, "star_index": 30}}}
+    }
+  }
+}"#;
+
+    #[test]
+    fn test_unlock_timestamp() {
+        // 2022-12-01T05:00:00Z
+        assert_eq!(1669870800, unlock_timestamp(2022, 1));
+        // 2021-12-25T05:00:00Z
+        assert_eq!(1640408400, unlock_timestamp(2021, 25));
+    }
+
+    #[test]
+    fn test_import() {
+        let members = Members::parse("\"2\" = \"mingweisamuel\"\n").unwrap();
+        let usernames: BTreeSet<_> = ["mingweisamuel", "stelar7", "slate"]
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+        let export = PrivateLeaderboard::parse(EXPORT).unwrap();
+        let (stars, unmapped) = Stars::import(&export, &members, &usernames).unwrap();
+        assert_eq!(2022, stars.year);
+        assert_eq!(vec!["anonymous user #3"], unmapped);
+        assert_eq!(
+            Some(&vec![1669871100, 1669871400]),
+            stars.days[&1].get("stelar7")
+        );
+        assert_eq!(Some(&vec![1669870900]), stars.days[&1].get("mingweisamuel"));
+
+        let mut leaderboard = Leaderboard::parse_text(
+            2022,
+            "
+-- Day 1 --
+Rust       mingweisamuel   3987ms     71ms       7ms        ✅
+05AB1E     stelar7         0ms        2503ms     539ms      ✅
+Python3    slate           0ms        161ms      24ms       ✅
+",
+        )
+        .unwrap();
+        stars.merge_into(&mut leaderboard);
+        assert_eq!(vec![100], leaderboard.entries[0].solved_s);
+        assert_eq!(vec![300, 600], leaderboard.entries[1].solved_s);
+        assert!(leaderboard.entries[2].solved_s.is_empty());
+
+        // `make test` rewrites the text leaderboard without solve times, which come back from
+        // `stars.json` when it's shown.
+        let dir = TempDir::new("stars");
+        stars.save(&dir).unwrap();
+        let mut plain = leaderboard.clone();
+        plain
+            .entries
+            .iter_mut()
+            .for_each(|entry| entry.solved_s.clear());
+        plain.save_txt(&dir).unwrap();
+        let loaded = Stars::load(&dir, 2022).unwrap();
+        let shown = leaderboard_with_stars(&dir, &loaded, 1, Rank::Solved).unwrap();
+        let authors: Vec<_> = shown
+            .unwrap()
+            .entries
+            .into_iter()
+            .map(|e| e.author)
+            .collect();
+        assert_eq!(vec!["stelar7", "mingweisamuel", "slate"], authors);
+    }
+}
@@ -0,0 +1,504 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::time::Duration;
+
+use crate::client::Client;
+use crate::config::{DayConfig, SolutionConfig};
+use crate::exec;
+use crate::history::{self, History, Submission};
+use crate::html;
+use crate::lang::Language;
+use crate::lint::AnswerLines;
+
+/// Assumed rate limit when a response doesn't say how long to wait.
+const DEFAULT_WAIT_SECS: i64 = 60;
+
+/// Submitting again after being told to wait is retried at most this often.
+const MAX_TOO_SOON: usize = 3;
+
+/// How adventofcode.com judged a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Submitted while rate limited, the answer wasn't checked.
+    TooSoon,
+    /// The part was already solved (e.g. in a browser), the answer wasn't checked.
+    AlreadySolved,
+}
+impl Verdict {
+    const ALL: [Self; 6] = [
+        Self::Right,
+        Self::Wrong,
+        Self::TooHigh,
+        Self::TooLow,
+        Self::TooSoon,
+        Self::AlreadySolved,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Right => "right",
+            Self::Wrong => "wrong",
+            Self::TooHigh => "too high",
+            Self::TooLow => "too low",
+            Self::TooSoon => "too soon",
+            Self::AlreadySolved => "already solved",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|verdict| verdict.as_str() == s)
+    }
+
+    fn is_wrong(self) -> bool {
+        matches!(self, Self::Wrong | Self::TooHigh | Self::TooLow)
+    }
+}
+
+/// The parsed `POST /{year}/day/{day}/answer` response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub verdict: Verdict,
+    /// Seconds until the next answer may be submitted, if the response says.
+    pub wait_secs: Option<i64>,
+}
+
+/// Parses the `<article>` of an answer response.
+pub fn parse_response(page: &str) -> Result<Response> {
+    let text = html::article_text(page).ok_or_else(|| anyhow!("No <article> in the response."))?;
+    let verdict = if text.contains("That's the right answer") {
+        Verdict::Right
+    } else if text.contains("That's not the right answer") {
+        if text.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if text.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if text.contains("You gave an answer too recently") {
+        Verdict::TooSoon
+    } else if text.contains("You don't seem to be solving the right level") {
+        Verdict::AlreadySolved
+    } else {
+        bail!("Unexpected response: {}", text);
+    };
+    Ok(Response {
+        verdict,
+        wait_secs: parse_wait(&text),
+    })
+}
+
+/// `You have 1m 5s left to wait.` or `Please wait one minute before trying again.`
+fn parse_wait(text: &str) -> Option<i64> {
+    if let Some((_, rest)) = text.split_once("You have ") {
+        let (left, _) = rest.split_once(" left to wait")?;
+        return left
+            .split_whitespace()
+            .map(|amount| {
+                let (n, unit) = amount.split_at(amount.len().checked_sub(1)?);
+                let n: i64 = n.parse().ok()?;
+                match unit {
+                    "s" => Some(n),
+                    "m" => Some(n * 60),
+                    "h" => Some(n * 3600),
+                    _ => None,
+                }
+            })
+            .sum();
+    }
+    let lower = text.to_lowercase();
+    let (_, rest) = lower.split_once("please wait ")?;
+    let (wait, _) = rest.split_once(" before trying again")?;
+    let (n, unit) = wait.split_once(' ')?;
+    let n = match n {
+        "one" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "ten" => 10,
+        n => n.parse().ok()?,
+    };
+    match unit.trim_end_matches('s') {
+        "second" => Some(n),
+        "minute" => Some(n * 60),
+        "hour" => Some(n * 3600),
+        _ => None,
+    }
+}
+
+/// Why `answer` is known to be wrong from earlier submissions of the same part, without
+/// asking again: it was rejected before, or is beyond a "too high"/"too low" bound.
+fn known_wrong(answer: &str, part: u32, submissions: &[Submission]) -> Option<String> {
+    let number = answer.parse::<i128>().ok();
+    submissions
+        .iter()
+        .filter(|submission| submission.part == part && submission.verdict.is_wrong())
+        .find_map(|submission| {
+            let bound = submission.answer.parse::<i128>().ok();
+            let beyond = match (submission.verdict, number, bound) {
+                _ if submission.answer == answer => true,
+                (Verdict::TooHigh, Some(number), Some(bound)) => number >= bound,
+                (Verdict::TooLow, Some(number), Some(bound)) => number <= bound,
+                _ => false,
+            };
+            beyond.then(|| {
+                format!(
+                    "{} was already submitted and was {}",
+                    submission.answer,
+                    submission.verdict.as_str()
+                )
+            })
+        })
+}
+
+/// Sleeps until `username` may submit again, or fails if that's more than `max_wait` seconds.
+fn wait_for_rate_limit(history: &History, username: &str, max_wait: u64) -> Result<()> {
+    let Some(until) = history.wait_until(username)? else {
+        return Ok(());
+    };
+    let left = until - history::now();
+    if left <= 0 {
+        return Ok(());
+    }
+    if left as u64 > max_wait {
+        bail!(
+            "Rate limited for another {}s, try again later or raise --max-wait.",
+            left
+        );
+    }
+    println!("Rate limited, waiting {}s.", left);
+    std::thread::sleep(Duration::from_secs(left as u64));
+    Ok(())
+}
+
+/// Submits each part's answer in order, unless it's already known to be right or wrong,
+/// and stops at the first that isn't right. Every response is recorded in `history`. Returns
+/// whether every part's answer is verified.
+pub fn submit_answers(
+    history: &History,
+    client: &Client,
+    year: u32,
+    day: u32,
+    username: &str,
+    answers: &[String],
+    max_wait: u64,
+) -> Result<bool> {
+    let submissions = history.submissions(year, day, username)?;
+    for (part, answer) in (1..).zip(answers) {
+        let right = submissions
+            .iter()
+            .find(|submission| submission.part == part && submission.verdict == Verdict::Right);
+        if let Some(right) = right {
+            if right.answer == *answer {
+                println!("Part {}: {} ✅ (already verified)", part, answer);
+                continue;
+            }
+            println!(
+                "Part {}: {} ❌ (the right answer is {})",
+                part, answer, right.answer
+            );
+            return Ok(false);
+        }
+        if let Some(reason) = known_wrong(answer, part, &submissions) {
+            println!("Part {}: {} ❌ ({})", part, answer, reason);
+            return Ok(false);
+        }
+
+        let mut tries = 0;
+        let verdict = loop {
+            wait_for_rate_limit(history, username, max_wait)?;
+            let response = parse_response(&client.answer(year, day, part, answer)?)?;
+            let now = history::now();
+            let wait_secs = match response.verdict {
+                Verdict::TooSoon => Some(response.wait_secs.unwrap_or(DEFAULT_WAIT_SECS)),
+                _ => response.wait_secs,
+            };
+            history.record_submission(&Submission {
+                timestamp: now,
+                year,
+                day,
+                username: username.to_owned(),
+                part,
+                answer: answer.clone(),
+                verdict: response.verdict,
+                wait_until: wait_secs.map(|secs| now + secs),
+            })?;
+            tries += 1;
+            if response.verdict != Verdict::TooSoon || tries >= MAX_TOO_SOON {
+                break response.verdict;
+            }
+        };
+        match verdict {
+            Verdict::Right => println!("Part {}: {} ✅", part, answer),
+            Verdict::AlreadySolved => {
+                println!(
+                    "Part {}: {} ❔ (already solved, so it wasn't checked)",
+                    part, answer
+                );
+                // Later parts may still be unsolved.
+                continue;
+            }
+            _ => {
+                println!("Part {}: {} ❌ ({})", part, answer, verdict.as_str());
+                return Ok(false);
+            }
+        }
+    }
+    // Only right answers reach here, or unchecked already solved ones.
+    let submissions = history.submissions(year, day, username)?;
+    Ok((1..).zip(answers).all(|(part, answer)| {
+        submissions.iter().any(|submission| {
+            submission.part == part
+                && submission.verdict == Verdict::Right
+                && submission.answer == *answer
+        })
+    }))
+}
+
+/// `username`'s solution of the day, optionally in `language`.
+fn find_solution<'a>(
+    day: &'a DayConfig,
+    username: &str,
+    language: Option<Language>,
+) -> Result<&'a SolutionConfig> {
+    let mut solutions = day.solutions.iter().filter(|solution| {
+        solution.author() == username && language.is_none_or(|lang| solution.language == lang)
+    });
+    let solution = solutions.next().ok_or_else(|| {
+        anyhow!(
+            "{}/{} has no solution by {} in test.sh.",
+            day.year,
+            day.day,
+            username
+        )
+    })?;
+    if solutions.next().is_some() {
+        bail!(
+            "{} has several solutions on {}/{}, pick one with --language.",
+            username,
+            day.year,
+            day.day
+        );
+    }
+    Ok(solution)
+}
+
+/// Runs `username`'s solution on `io/{username}.input`, submits its answers and, once they're
+/// all right, writes them to `io/{username}.output`. Returns whether the output is verified.
+pub fn submit(
+    root: &std::path::Path,
+    history: &History,
+    client: &Client,
+    day: &DayConfig,
+    username: &str,
+    language: Option<Language>,
+    max_wait: u64,
+) -> Result<bool> {
+    let solution = find_solution(day, username, language)?;
+    let input = day.io_dir().join(format!("{}.input", username));
+    let output = input.with_extension("output");
+    if !input.is_file() {
+        bail!("Missing {}, `fetch` it first.", input.display());
+    }
+
+    solution.language.build(&solution.path)?;
+    let run = exec::run_once(solution, &input)?;
+    if run.timed_out || !run.status.is_some_and(|status| status.success()) {
+        bail!(
+            "{} {} crashed or timed out on {}.",
+            solution.language.display_name(),
+            username,
+            input.display()
+        );
+    }
+    if let Ok(expected) = std::fs::read(&output) {
+        if exec::outputs_match(&expected, &run.stdout) {
+            println!("{} already has the solution's answers.", output.display());
+            return Ok(true);
+        }
+        bail!(
+            "The solution's answers differ from {}, which is already verified.",
+            output.display()
+        );
+    }
+
+    let stdout = String::from_utf8_lossy(&run.stdout);
+    let lines: Vec<String> = stdout
+        .lines()
+        .map(|line| line.trim_end().to_owned())
+        .collect();
+    let expected_lines = AnswerLines::load(root)?.expected(day.year, day.day, username);
+    if lines.len() != expected_lines {
+        bail!(
+            "The solution printed {} line(s), expected {}.",
+            lines.len(),
+            expected_lines
+        );
+    }
+    // Multi-line answers (2022/10's CRT) have to be read and submitted by hand.
+    let answers = match expected_lines {
+        1 | 2 => &lines[..],
+        _ => {
+            println!("Part 2 spans {} lines, submit it by hand.", lines.len() - 1);
+            &lines[..1]
+        }
+    };
+    let verified = submit_answers(
+        history, client, day.year, day.day, username, answers, max_wait,
+    )?;
+    if !verified || answers.len() != lines.len() {
+        return Ok(false);
+    }
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    std::fs::write(&output, contents)
+        .with_context(|| format!("Failed to write {}.", output.display()))?;
+    println!("Wrote {}", output.display());
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockServer;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn test_parse_response() {
+        let response = |text: &str| {
+            parse_response(&format!(
+                "<main>\n<article><p>{}</p></article>\n</main>",
+                text
+            ))
+            .unwrap()
+        };
+        assert_eq!(
+            Response {
+                verdict: Verdict::Right,
+                wait_secs: None
+            },
+            response("That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer.")
+        );
+        assert_eq!(
+            Response {
+                verdict: Verdict::TooHigh,
+                wait_secs: Some(60)
+            },
+            response("That's not the right answer; your answer is too high.  If you're stuck, ... Please wait one minute before trying again. <a href=\"/2022/day/1\">[Return to Day 1]</a>")
+        );
+        assert_eq!(
+            Response {
+                verdict: Verdict::Wrong,
+                wait_secs: Some(300)
+            },
+            response("That's not the right answer.  please wait 5 minutes before trying again.")
+        );
+        assert_eq!(
+            Response {
+                verdict: Verdict::TooSoon,
+                wait_secs: Some(65)
+            },
+            response("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait.")
+        );
+        assert_eq!(
+            Verdict::AlreadySolved,
+            response("You don't seem to be solving the right level.  Did you already complete it?")
+                .verdict
+        );
+        assert!(parse_response("<article><p>Huh?</p></article>").is_err());
+        for verdict in Verdict::ALL {
+            assert_eq!(Some(verdict), Verdict::parse(verdict.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_known_wrong() {
+        let submission = |answer: &str, verdict| Submission {
+            timestamp: 0,
+            year: 2022,
+            day: 1,
+            username: "someone".to_owned(),
+            part: 1,
+            answer: answer.to_owned(),
+            verdict,
+            wait_until: None,
+        };
+        let submissions = [
+            submission("100", Verdict::TooHigh),
+            submission("10", Verdict::TooLow),
+            submission("ABC", Verdict::Wrong),
+        ];
+        assert!(known_wrong("100", 1, &submissions).is_some());
+        assert!(known_wrong("150", 1, &submissions).is_some());
+        assert!(known_wrong("5", 1, &submissions).is_some());
+        assert!(known_wrong("ABC", 1, &submissions).is_some());
+        assert_eq!(None, known_wrong("50", 1, &submissions));
+        assert_eq!(None, known_wrong("150", 2, &submissions));
+    }
+
+    #[test]
+    fn test_submit_answers() {
+        let dir = TempDir::new("submit");
+        let day_dir = dir.join("2022/day/1");
+        std::fs::create_dir_all(&day_dir).unwrap();
+        std::fs::write(day_dir.join("answers"), "24000\n45000\n").unwrap();
+        let answers = |part1: &str, part2: &str| vec![part1.to_owned(), part2.to_owned()];
+
+        let history = History::open_in_memory().unwrap();
+        let server = MockServer::start(dir.to_path_buf(), 0, Duration::ZERO).unwrap();
+        let client = Client::new(&server.url(), Some("secret".to_owned()), dir.join("cache"));
+        let too_low = submit_answers(&history, &client, 2022, 1, "a", &answers("1", "2"), 0);
+        let known = submit_answers(&history, &client, 2022, 1, "a", &answers("0", "2"), 0);
+        let right = submit_answers(
+            &history,
+            &client,
+            2022,
+            1,
+            "a",
+            &answers("24000", "45000"),
+            0,
+        );
+        let again = submit_answers(
+            &history,
+            &client,
+            2022,
+            1,
+            "a",
+            &answers("24000", "45000"),
+            0,
+        );
+        let submissions = history.submissions(2022, 1, "a").unwrap();
+        let requests = server.requests().len();
+
+        // A minute's rate limit after a wrong answer.
+        let server = MockServer::start(dir.to_path_buf(), 0, Duration::from_secs(60)).unwrap();
+        let client = Client::new(&server.url(), Some("secret".to_owned()), dir.join("cache"));
+        let wrong = submit_answers(&history, &client, 2022, 1, "b", &answers("1", "2"), 0);
+        let limited = submit_answers(&history, &client, 2022, 1, "b", &answers("24000", "2"), 0);
+        let limited_requests = server.requests().len();
+
+        assert!(!too_low.unwrap());
+        assert!(!known.unwrap());
+        assert!(right.unwrap());
+        assert!(again.unwrap());
+        assert_eq!(
+            vec![
+                (1, "1", Verdict::TooLow),
+                (1, "24000", Verdict::Right),
+                (2, "45000", Verdict::Right)
+            ],
+            submissions
+                .iter()
+                .map(|s| (s.part, &*s.answer, s.verdict))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(3, requests);
+        assert!(!wrong.unwrap());
+        assert!(limited.is_err());
+        assert_eq!(1, limited_requests);
+    }
+}
@@ -0,0 +1,48 @@
+use std::collections::BTreeMap;
+use std::process::Stdio;
+use std::time::Duration;
+use wait_timeout::ChildExt;
+
+use crate::lang::Language;
+
+/// Version string of each available toolchain, keyed by `lang/{script}.sh` name.
+pub type Toolchains = BTreeMap<String, String>;
+
+/// Probes the version of every language's compiler or interpreter. Missing toolchains are
+/// left out.
+pub fn probe() -> Toolchains {
+    Language::ALL
+        .into_iter()
+        .filter_map(|lang| Some((lang.script().to_owned(), version(lang)?)))
+        .collect()
+}
+
+fn version(lang: Language) -> Option<String> {
+    let mut cmd = lang.version_command();
+    let program = cmd.get_program().to_string_lossy().to_lowercase();
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+    if child.wait_timeout(Duration::from_secs(10)).ok()?.is_none() {
+        let _ = child.kill();
+        let _ = child.wait();
+        return None;
+    }
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // Some print to stderr (`java -version`), and some print other banners first (`elixir`), so
+    // prefer the line naming the program, then any line with a version number in it.
+    let text = [output.stdout, output.stderr]
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .join("\n");
+    let lines = || text.lines().map(str::trim);
+    lines()
+        .find(|line| line.to_lowercase().starts_with(&program))
+        .or_else(|| lines().find(|line| line.chars().any(|c| c.is_ascii_digit())))
+        .map(str::to_owned)
+}
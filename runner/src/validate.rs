@@ -0,0 +1,276 @@
+use anyhow::Result;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+use crate::config::{self, DayConfig};
+use crate::lang::Language;
+
+/// A broken rule of the README's repository layout.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Violation {
+    pub path: PathBuf,
+    pub message: String,
+}
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+struct Violations<'a> {
+    root: &'a Path,
+    list: Vec<Violation>,
+}
+impl Violations<'_> {
+    fn push(&mut self, path: &Path, message: impl Into<String>) {
+        let path = path.strip_prefix(self.root).unwrap_or(path).to_owned();
+        self.list.push(Violation {
+            path,
+            message: message.into(),
+        });
+    }
+}
+
+fn sorted_entries(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries = std::fs::read_dir(dir)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?;
+    entries.sort();
+    Ok(entries)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Checks every day of `year` (or just `day`) against the README's rules.
+pub fn validate(root: &Path, year: u32, day: Option<u32>) -> Result<Vec<Violation>> {
+    let mut violations = Violations {
+        root,
+        list: Vec::new(),
+    };
+    let year_dir = root.join(year.to_string());
+    for day_dir in sorted_entries(&year_dir)? {
+        let Ok(d) = file_name(&day_dir).parse::<u32>() else {
+            continue;
+        };
+        if day.is_some_and(|day| day != d) || !day_dir.is_dir() {
+            continue;
+        }
+        validate_day(&mut violations, root, year, d)?;
+    }
+    violations.list.sort();
+    Ok(violations.list)
+}
+
+fn validate_day(violations: &mut Violations, root: &Path, year: u32, day: u32) -> Result<()> {
+    let day_dir = root.join(year.to_string()).join(day.to_string());
+    let io_dir = day_dir.join("io");
+
+    // `solutions/{language}/{username}` directories.
+    let mut solution_dirs = BTreeSet::new();
+    let solutions_dir = day_dir.join("solutions");
+    if solutions_dir.is_dir() {
+        for lang_dir in sorted_entries(&solutions_dir)? {
+            if !lang_dir.is_dir() {
+                violations.push(
+                    &lang_dir,
+                    "source must be in `solutions/{language}/{username}`",
+                );
+                continue;
+            }
+            let lang_name = file_name(&lang_dir);
+            if !Language::ALL
+                .iter()
+                .any(|lang| lang.dir_name() == lang_name)
+            {
+                let known = Language::ALL
+                    .iter()
+                    .map(|lang| lang.dir_name())
+                    .collect::<BTreeSet<_>>();
+                violations.push(
+                    &lang_dir,
+                    format!(
+                        "unknown language directory `{}`, expected one of: {}",
+                        lang_name,
+                        known.into_iter().collect::<Vec<_>>().join(", ")
+                    ),
+                );
+            }
+            for user_dir in sorted_entries(&lang_dir)? {
+                if user_dir.is_dir() {
+                    solution_dirs.insert(user_dir);
+                } else {
+                    violations.push(
+                        &user_dir,
+                        "source must be in a `{username}` directory under the language",
+                    );
+                }
+            }
+        }
+    }
+
+    // Solutions registered in `test.sh`.
+    let mut registered = BTreeSet::new();
+    let test_sh = day_dir.join("test.sh");
+    if !test_sh.is_file() {
+        violations.push(&test_sh, "missing");
+    } else {
+        match DayConfig::load(root, year, day) {
+            Err(err) => violations.push(&test_sh, format!("{:#}", err)),
+            Ok(config) => {
+                for solution in &config.solutions {
+                    validate_solution(violations, &day_dir, &io_dir, solution, &mut registered);
+                }
+            }
+        }
+    }
+    for dir in solution_dirs.difference(&registered) {
+        violations.push(dir, "not registered in `test.sh`");
+    }
+
+    // io files.
+    if !io_dir.is_dir() {
+        violations.push(&io_dir, "missing");
+        return Ok(());
+    }
+    for path in sorted_entries(&io_dir)? {
+        let counterpart = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("input") => "output",
+            Some("output") => "input",
+            _ => {
+                violations.push(&path, "io files must be `{username}.input` or `.output`");
+                continue;
+            }
+        };
+        if !path.with_extension(counterpart).is_file() {
+            violations.push(&path, format!("missing matching `.{}`", counterpart));
+        }
+        let contents = std::fs::read(&path)?;
+        if !contents.ends_with(b"\n") {
+            violations.push(&path, "missing trailing newline");
+        }
+    }
+    Ok(())
+}
+
+fn validate_solution(
+    violations: &mut Violations,
+    day_dir: &Path,
+    io_dir: &Path,
+    solution: &config::SolutionConfig,
+    registered: &mut BTreeSet<PathBuf>,
+) {
+    let lang = solution.language;
+    if let Some(entry) = lang.missing_entry_file(&solution.path) {
+        violations.push(
+            &solution.path,
+            format!(
+                "missing entry file `{}`",
+                entry
+                    .strip_prefix(violations.root)
+                    .unwrap_or(&entry)
+                    .display()
+            ),
+        );
+    }
+
+    let relative = solution
+        .path
+        .strip_prefix(day_dir)
+        .unwrap_or(&solution.path);
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| match c {
+            Component::Normal(part) => part.to_string_lossy().into_owned(),
+            _ => String::new(),
+        })
+        .collect();
+    // `solutions/{language}/{username}` for projects, plus the file name otherwise.
+    let depth = if lang.is_project_dir() { 3 } else { 4 };
+    if parts.len() != depth || parts[0] != "solutions" {
+        violations.push(
+            &solution.path,
+            "source must be in `solutions/{language}/{username}`",
+        );
+        return;
+    }
+    registered.insert(day_dir.join(&parts[0]).join(&parts[1]).join(&parts[2]));
+    if parts[1] != lang.dir_name() {
+        violations.push(
+            &solution.path,
+            format!(
+                "`{}.sh` solutions belong in `solutions/{}`",
+                lang.script(),
+                lang.dir_name()
+            ),
+        );
+    }
+
+    let author = solution.author();
+    for ext in ["input", "output"] {
+        let io_file = io_dir.join(format!("{}.{}", author, ext));
+        if !io_file.is_file() {
+            violations.push(
+                &io_file,
+                format!("missing, required for the solution of `{}`", author),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    fn write(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_validate() {
+        let root = TempDir::new("validate");
+        let day = root.join("2021/3");
+        write(
+            &day.join("test.sh"),
+            r#"
+$D/../../lang/python3.sh "$D/solutions/python3/kurainu/main.py" "$D/io/*"
+$D/../../lang/node.sh "$D/solutions/node/stelar7/main.mjs" "$D/io/*"
+$D/../../lang/cargo_rust.sh "$D/solutions/rust/mingweisamuel" "$D/io/*"
+"#,
+        );
+        write(&day.join("solutions/python3/kurainu/main.py"), "");
+        write(&day.join("solutions/node/stelar7/main.mjs"), "");
+        write(&day.join("solutions/rust/mingweisamuel/src/main.rs"), "");
+        write(&day.join("solutions/d3/d3.go"), "");
+        write(&day.join("solutions/main.go"), "");
+        write(&day.join("io/kurainu.input"), "1\n");
+        write(&day.join("io/kurainu.output"), "1\n2\n");
+        write(&day.join("io/stelar7.input"), "1");
+        write(&day.join("io/stelar7.output"), "1\n2\n");
+        write(&day.join("io/mingweisamuel.input"), "1\n");
+
+        let violations = validate(&root, 2021, None).unwrap();
+
+        let violations: Vec<_> = violations.iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            vec![
+                "2021/3/io/mingweisamuel.input: missing matching `.output`",
+                "2021/3/io/mingweisamuel.output: missing, required for the solution of `mingweisamuel`",
+                "2021/3/io/stelar7.input: missing trailing newline",
+                "2021/3/solutions/d3: unknown language directory `d3`, expected one of: 05ab1e, cmake_cpp, dotnet_c_sharp, golang, golfscript, java, mono_c_sharp, node, perl, python, rust",
+                "2021/3/solutions/d3/d3.go: source must be in a `{username}` directory under the language",
+                "2021/3/solutions/main.go: source must be in `solutions/{language}/{username}`",
+                "2021/3/solutions/python3: unknown language directory `python3`, expected one of: 05ab1e, cmake_cpp, dotnet_c_sharp, golang, golfscript, java, mono_c_sharp, node, perl, python, rust",
+                "2021/3/solutions/python3/kurainu/main.py: `python3.sh` solutions belong in `solutions/python`",
+                "2021/3/solutions/rust/mingweisamuel: missing entry file `2021/3/solutions/rust/mingweisamuel/Cargo.toml`",
+            ],
+            violations
+        );
+    }
+}
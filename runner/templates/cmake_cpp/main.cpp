@@ -0,0 +1,15 @@
+#include <iostream>
+#include <string>
+#include <vector>
+
+int main()
+{
+	std::vector<std::string> lines;
+	for (std::string line; std::getline(std::cin, line);)
+		lines.push_back(line);
+
+	long long part1 = 0;
+	std::cout << part1 << '\n';
+	long long part2 = 0;
+	std::cout << part2 << '\n';
+}
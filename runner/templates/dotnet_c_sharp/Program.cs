@@ -0,0 +1,6 @@
+var lines = Console.In.ReadToEnd().TrimEnd().Split('\n');
+
+long part1 = 0;
+Console.WriteLine(part1);
+long part2 = 0;
+Console.WriteLine(part2);
@@ -0,0 +1,3 @@
+module {{username}}/aoc{{year}}/day{{day}}
+
+go 1.18
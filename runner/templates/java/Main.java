@@ -0,0 +1,16 @@
+import java.io.BufferedReader;
+import java.io.InputStreamReader;
+import java.util.List;
+import java.util.stream.Collectors;
+
+public class Main {
+    public static void main(String[] args) throws Exception {
+        BufferedReader reader = new BufferedReader(new InputStreamReader(System.in));
+        List<String> lines = reader.lines().collect(Collectors.toList());
+
+        long part1 = 0;
+        System.out.println(part1);
+        long part2 = 0;
+        System.out.println(part2);
+    }
+}
@@ -0,0 +1,14 @@
+using System;
+
+public static class Program
+{
+    public static void Main()
+    {
+        var lines = Console.In.ReadToEnd().TrimEnd().Split('\n');
+
+        long part1 = 0;
+        Console.WriteLine(part1);
+        long part2 = 0;
+        Console.WriteLine(part2);
+    }
+}
@@ -0,0 +1,8 @@
+import { readFileSync } from "fs";
+
+const lines = readFileSync(0, "utf8").trimEnd().split("\n");
+
+const part1 = 0;
+console.log(part1);
+const part2 = 0;
+console.log(part2);
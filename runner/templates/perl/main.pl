@@ -0,0 +1,9 @@
+use strict;
+use warnings;
+
+chomp(my @lines = <STDIN>);
+
+my $part1 = 0;
+print "$part1\n";
+my $part2 = 0;
+print "$part2\n";
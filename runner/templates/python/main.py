@@ -0,0 +1,8 @@
+import sys
+
+lines = sys.stdin.read().splitlines()
+
+part1 = 0
+print(part1)
+part2 = 0
+print(part2)
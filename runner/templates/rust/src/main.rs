@@ -0,0 +1,11 @@
+use std::io::Read;
+
+fn main() {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).unwrap();
+
+    let part1 = 0;
+    println!("{}", part1);
+    let part2 = 0;
+    println!("{}", part2);
+}